@@ -1,14 +1,35 @@
-use soroban_sdk::{contract, contractimpl, log, vec, Address, BytesN, Env, Vec};
+use soroban_sdk::{contract, contractimpl, log, vec, xdr::ToXdr, Address, Bytes, BytesN, Env, Vec};
 
 use crate::{
-    collection,
+    collection, deployer,
     error::ContractError,
     storage::{
-        generate_auction_id, get_admin_old, get_auction_by_id, get_auction_token, get_auctions,
-        get_auctions_by_seller_id, get_highest_bid, is_initialized, save_admin_old,
-        save_auction_by_id, save_auction_by_seller, save_auction_token, set_highest_bid,
-        set_initialized, update_admin, validate_input_params, Auction, AuctionStatus, HighestBid,
-        ItemInfo,
+        accrue_fees, generate_auction_id, generate_listing_id, generate_offer_id,
+        get_accepted_token, get_accepted_tokens, get_accrued_fees, get_admin_old,
+        get_auction_by_id, get_auctions, get_auctions_by_collection,
+        get_auctions_by_highest_bidder, get_auctions_by_item, get_auctions_by_seller,
+        get_auctions_by_status, get_bid_increment, get_bidder_restriction, get_bids,
+        get_bids_by_bidder, get_bundle_items, get_collection_verification, get_config,
+        get_current_auction, get_current_auction_id, get_dutch_auction, get_ending_soon_auctions,
+        get_highest_bid, get_legacy_config, get_listing_by_id, get_listings,
+        get_listings_by_seller, get_offer_by_id, get_offers_by_buyer, get_offers_by_collection,
+        get_pending_refund, get_sealed_auction, get_sealed_bid, get_sealed_bidders,
+        get_total_extension, get_upcoming_auctions, has_active_listings, has_unescrowed_auctions,
+        is_collection_blocked, is_collection_verified, is_initialized, is_item_escrowed,
+        is_marketplace_paused, is_seller_blocked, migrate_auction, record_bid,
+        save_accepted_tokens, save_accrued_fees, save_admin_old, save_auction_by_id,
+        save_auction_by_seller, save_bidder_restriction, save_bundle_items,
+        save_collection_verification, save_config, save_dutch_auction, save_listing, save_offer,
+        save_sealed_auction, save_sealed_bid, set_bid_increment, set_collection_blocked,
+        set_collection_verified, set_highest_bid, set_initialized, set_item_escrowed,
+        set_marketplace_paused, set_pending_refund, set_seller_blocked, set_start_time,
+        set_total_extension, update_admin, validate_bps, validate_input_params, AcceptedToken,
+        AntiSnipingConfig, Auction, AuctionPage, AuctionStatus, Bid, BidIncrement,
+        BidderRestriction, BundleItem, CollectionVerification, Config, DutchAuctionInfo,
+        DutchPriceSchedule, HighestBid, ItemInfo, Listing, ListingStatus, Offer, OfferItem,
+        OfferStatus, PriceDecay, SealedAuctionInfo, SealedAuctionParams, SealedBid,
+        SealedBidPricing, TimedAuctionPage, DEFAULT_INDEX, DEFAULT_LIMIT, MAX_BASIS_POINTS,
+        MAX_BUNDLE_SIZE,
     },
     token,
 };
@@ -24,6 +45,9 @@ impl MarketplaceContract {
         admin: Address,
         auction_token: Address,
         auction_creation_fee: u128,
+        max_royalty_bps: u32,
+        protocol_fee_bps: u32,
+        treasury: Address,
     ) -> Result<(), ContractError> {
         admin.require_auth();
 
@@ -32,14 +56,19 @@ impl MarketplaceContract {
             return Err(ContractError::AlreadyInitialized);
         }
 
+        // royalty and protocol fee are both taken from the sale price
+        validate_bps(&env, max_royalty_bps.saturating_add(protocol_fee_bps))?;
+
         save_admin_old(&env, &admin);
 
-        let config = Config {
+        save_initial_config(
+            &env,
             auction_token,
             auction_creation_fee,
-        };
-
-        save_config(&env, config);
+            max_royalty_bps,
+            protocol_fee_bps,
+            treasury,
+        );
 
         set_initialized(&env);
 
@@ -54,8 +83,13 @@ impl MarketplaceContract {
         item_info: ItemInfo,
         seller: Address,
         duration: u64,
+        auction_token: Option<Address>,
+        start_time: Option<u64>,
     ) -> Result<Auction, ContractError> {
         seller.require_auth();
+        ensure_marketplace_active(&env)?;
+        ensure_allowed_seller(&env, &seller, &item_info.collection_addr)?;
+        ensure_verified_collection(&env, &item_info.collection_addr)?;
 
         let input_values = [
             &duration,
@@ -70,8 +104,33 @@ impl MarketplaceContract {
         validate_input_params(&env, &input_values[..])?;
 
         let config = get_config(&env)?;
-        let auction_token = config.auction_token;
-        let auction_creation_fee = config.auction_creation_fee as i128;
+        let accepted_token = get_accepted_token(
+            &env,
+            &auction_token.unwrap_or_else(|| config.auction_token.clone()),
+        )?;
+        let auction_token = accepted_token.token;
+        let auction_creation_fee = accepted_token.creation_fee as i128;
+
+        if item_info
+            .buy_now_price
+            .is_some_and(|price| price < accepted_token.min_price)
+            || item_info
+                .minimum_price
+                .is_some_and(|price| price < accepted_token.min_price)
+        {
+            log!(
+                &env,
+                "Auction: Create Auction: Price below the minimum of the token: ",
+                accepted_token.min_price
+            );
+            return Err(ContractError::InvalidInputs);
+        }
+
+        // the minimum price of the token acts as reserve when the seller sets none
+        let mut item_info = item_info;
+        if item_info.minimum_price.is_none() && accepted_token.min_price > 0 {
+            item_info.minimum_price = Some(accepted_token.min_price);
+        }
 
         let token_client = token::Client::new(&env, &auction_token);
 
@@ -90,11 +149,13 @@ impl MarketplaceContract {
             &env.current_contract_address(),
             &auction_creation_fee,
         );
+        accrue_fees(&env, &auction_token, auction_creation_fee as u128);
 
         let nft_client = collection::Client::new(&env, &item_info.collection_addr);
         let item_balance = nft_client.balance_of(&seller, &item_info.item_id);
 
         nft_client.set_approval_for_transfer(
+            &seller,
             &env.current_contract_address(),
             &item_info.item_id,
             &true,
@@ -109,8 +170,26 @@ impl MarketplaceContract {
             return Err(ContractError::NotEnoughBalance);
         }
 
+        // announced auctions are scheduled and open for bids only once they start
+        let now = env.ledger().timestamp();
+        let start_time = start_time.unwrap_or(now);
+        if start_time < now {
+            log!(
+                &env,
+                "Auction: Create Auction: Start time is in the past: ",
+                start_time
+            );
+            return Err(ContractError::InvalidInputs);
+        }
+
+        let status = if start_time > now {
+            AuctionStatus::Scheduled
+        } else {
+            AuctionStatus::Active
+        };
+
         let id = generate_auction_id(&env)?;
-        let end_time = env.ledger().timestamp() + duration;
+        let end_time = start_time + duration;
 
         let auction = Auction {
             id,
@@ -118,339 +197,2081 @@ impl MarketplaceContract {
             seller: seller.clone(),
             highest_bid: None,
             end_time,
-            status: AuctionStatus::Active,
+            status,
             auction_token,
         };
 
+        // the marketplace holds the item until the auction is settled, so that the winner is
+        // guaranteed delivery
+        nft_client.safe_transfer_from(
+            &env.current_contract_address(),
+            &seller,
+            &env.current_contract_address(),
+            &auction.item_info.item_id,
+            &auction.item_info.amount,
+        );
+
+        if auction.status == AuctionStatus::Scheduled {
+            set_start_time(&env, id, start_time);
+        }
         save_auction(&env, &auction)?;
+        set_item_escrowed(&env, id);
+        set_bid_increment(&env, id, &config.min_bid_increment);
+
+        revoke_approval_if_unlisted(
+            &env,
+            &seller,
+            &auction.item_info.collection_addr,
+            auction.item_info.item_id,
+        )?;
 
         env.events()
             .publish(("create auction", "auction id: "), auction.id);
         env.events().publish(("create auction", "seller: "), seller);
         env.events().publish(("initialize", "duration: "), duration);
+        if auction.status == AuctionStatus::Scheduled {
+            env.events()
+                .publish(("create auction", "start time: "), start_time);
+        }
 
         Ok(auction)
     }
 
     #[allow(dead_code)]
-    pub fn place_bid(
+    pub fn create_dutch_auction(
         env: Env,
-        auction_id: u64,
-        bidder: Address,
-        bid_amount: u64,
-    ) -> Result<(), ContractError> {
-        bidder.require_auth();
-
-        let mut auction = get_auction_by_id(&env, auction_id)?;
+        item_info: ItemInfo,
+        seller: Address,
+        duration: u64,
+        auction_token: Option<Address>,
+        schedule: DutchPriceSchedule,
+    ) -> Result<Auction, ContractError> {
+        let DutchPriceSchedule {
+            start_price,
+            end_price,
+            decay,
+        } = schedule;
 
-        if env.ledger().timestamp() > auction.end_time {
-            log!(&env, "Auction: Place Bid: Auction not active: ", auction_id);
-            return Err(ContractError::AuctionNotActive);
-        }
+        validate_input_params(&env, &[&start_price, &end_price])?;
 
-        if auction.status != AuctionStatus::Active {
+        // the item is sold for the current price only, reserve and buy now prices do not apply
+        if item_info.minimum_price.is_some() || item_info.buy_now_price.is_some() {
             log!(
                 &env,
-                "Auction: Place Bid: Trying to place a bid for inactive/cancelled auction with id: ", auction_id
+                "Auction: Create Dutch Auction: Minimum and buy now price must not be set"
             );
-            return Err(ContractError::AuctionNotActive);
+            return Err(ContractError::InvalidInputs);
         }
 
-        if bidder == auction.seller {
-            log!(&env, "Auction Place Bid: Seller cannot place bids.");
-            return Err(ContractError::InvalidBidder);
+        if start_price <= end_price {
+            log!(
+                &env,
+                "Auction: Create Dutch Auction: Start price must be higher than the end price"
+            );
+            return Err(ContractError::InvalidInputs);
         }
 
-        let token_client = token::Client::new(&env, &auction.auction_token);
-
-        match auction.highest_bid {
-            Some(current_highest_bid) if bid_amount > current_highest_bid => {
-                // refund the previous highest bidder
-                let old_bid_info = get_highest_bid(&env, auction_id)?;
-                token_client.transfer(
-                    &env.current_contract_address(),
-                    &old_bid_info.bidder,
-                    &(old_bid_info.bid as i128),
-                );
-            }
-            Some(_) => {
+        if let PriceDecay::Stepwise(step) = decay {
+            if step == 0 || step > duration {
                 log!(
                     &env,
-                    "Auction: Place Bid: Bid not enough. Amount bid: ",
-                    bid_amount
+                    "Auction: Create Dutch Auction: Invalid price decay step: ",
+                    step
                 );
-                return Err(ContractError::BidNotEnough);
+                return Err(ContractError::InvalidInputs);
             }
-            None => {}
-        };
-
-        token_client.transfer(
-            &bidder,
-            &env.current_contract_address(),
-            &(bid_amount as i128),
-        );
-
-        set_highest_bid(&env, auction_id, bid_amount, bidder.clone())?;
-
-        auction.highest_bid = Some(bid_amount);
-        save_auction(&env, &auction)?;
-
-        env.events()
-            .publish(("place bid", "auction id"), auction_id);
-        env.events().publish(("place bid", "bidder"), bidder);
-        env.events().publish(("place bid", "bid"), bid_amount);
-
-        Ok(())
-    }
+        }
 
-    #[allow(dead_code)]
-    pub fn finalize_auction(env: Env, auction_id: u64) -> Result<(), ContractError> {
-        let mut auction = get_auction_by_id(&env, auction_id)?;
+        let auction = Self::create_auction(
+            env.clone(),
+            item_info,
+            seller,
+            duration,
+            auction_token,
+            None,
+        )?;
 
-        // Check if the auction can be finalized
-        if auction.status != AuctionStatus::Active {
+        if end_price < get_accepted_token(&env, &auction.auction_token)?.min_price {
             log!(
-                env,
-                "Auction: Finalize auction: Cannot finalize an inactive/ended auction."
-            );
-            return Err(ContractError::AuctionNotActive);
-        }
-        if env.ledger().timestamp() < auction.end_time {
-            log!(
-                env,
-                "Auction: Finalize auction: Auction cannot be ended early"
+                &env,
+                "Auction: Create Dutch Auction: End price below the minimum of the token"
             );
-            return Err(ContractError::AuctionNotFinished);
+            return Err(ContractError::InvalidInputs);
         }
 
-        let token_client = token::Client::new(&env, &auction.auction_token);
-        let highest_bid = get_highest_bid(&env, auction_id)?;
-
-        // check if minimum price has been reached
-        if auction.item_info.minimum_price.map_or(true, |min_price| {
-            auction
-                .highest_bid
-                .map_or(false, |highest_bid| highest_bid >= min_price)
-        }) {
-            token_client.transfer(
-                &env.current_contract_address(),
-                &auction.seller,
-                &(highest_bid.bid as i128),
-            );
+        let dutch_auction = DutchAuctionInfo {
+            start_price,
+            end_price,
+            start_time: env.ledger().timestamp(),
+            duration,
+            decay,
+        };
+        save_dutch_auction(&env, auction.id, &dutch_auction);
 
-            let nft_client = collection::Client::new(&env, &auction.item_info.collection_addr);
-            nft_client.safe_transfer_from(
-                &env.current_contract_address(),
-                &auction.seller,
-                &highest_bid.bidder,
-                &auction.item_info.item_id,
-                &auction.item_info.amount,
-            );
+        env.events()
+            .publish(("create dutch auction", "auction id: "), auction.id);
+        env.events()
+            .publish(("create dutch auction", "price schedule: "), dutch_auction);
 
-            auction.status = AuctionStatus::Ended;
-            save_auction(&env, &auction)?;
-            env.events()
-                .publish(("finalize auction", "highest bidder: "), highest_bid.bidder);
-            env.events()
-                .publish(("finalize auction", "highest bid: "), highest_bid.bid);
-        } else if auction.highest_bid.is_none() {
-            auction.status = AuctionStatus::Ended;
-            save_auction(&env, &auction)?;
+        Ok(auction)
+    }
 
-            env.events().publish(("finalize auction", "no bids"), ());
-        } else {
-            token_client.transfer(
-                &env.current_contract_address(),
-                &highest_bid.bidder,
-                &(highest_bid.bid as i128),
-            );
-            auction.status = AuctionStatus::Ended;
-            save_auction(&env, &auction)?;
+    #[allow(dead_code)]
+    pub fn current_price(env: Env, auction_id: u64) -> Result<u64, ContractError> {
+        let dutch_auction = get_dutch_auction(&env, auction_id).ok_or_else(|| {
             log!(
-                env,
-                "Auction: Finalize auction: Miniminal price not reached"
-            );
-
-            env.events()
-                .publish(("finalize auction", "auction id: "), auction_id);
-            env.events()
-                .publish(("finalize auction", "highest bid: "), auction.highest_bid);
-            env.events().publish(
-                ("finalize auction", "minimum price: "),
-                auction.item_info.minimum_price,
+                &env,
+                "Auction: Current price: Not a dutch auction: ",
+                auction_id
             );
-        };
+            ContractError::InvalidAuctionType
+        })?;
 
-        Ok(())
+        Ok(dutch_auction.price_at(env.ledger().timestamp()))
     }
 
     #[allow(dead_code)]
-    pub fn buy_now(env: Env, auction_id: u64, buyer: Address) -> Result<(), ContractError> {
+    pub fn buy_dutch_auction(
+        env: Env,
+        auction_id: u64,
+        buyer: Address,
+    ) -> Result<u64, ContractError> {
         buyer.require_auth();
+        ensure_marketplace_active(&env)?;
 
         let mut auction = get_auction_by_id(&env, auction_id)?;
 
-        if env.ledger().timestamp() > auction.end_time || auction.status != AuctionStatus::Active {
-            log!(&env, "Auction: Buy Now: Auction not active: ", auction_id);
-            return Err(ContractError::AuctionNotActive);
-        }
-
-        if auction.item_info.buy_now_price.is_none() {
+        let dutch_auction = get_dutch_auction(&env, auction_id).ok_or_else(|| {
             log!(
-                env,
-                "Auction: Buy Now: trying to buy an item that does not allow `buy now`"
+                &env,
+                "Auction: Buy Dutch Auction: Not a dutch auction: ",
+                auction_id
             );
-            return Err(ContractError::NoBuyNowOption);
-        }
+            ContractError::InvalidAuctionType
+        })?;
 
-        let old_highest_bid = get_highest_bid(&env, auction_id)?;
-
-        let token = token::Client::new(&env, &auction.auction_token);
-
-        // refund only when there is some previous highest bid
-        if old_highest_bid.bid > 0 {
-            token.transfer(
-                &env.current_contract_address(),
-                &old_highest_bid.bidder,
-                &(old_highest_bid.bid as i128),
+        if env.ledger().timestamp() > auction.end_time || auction.status != AuctionStatus::Active {
+            log!(
+                &env,
+                "Auction: Buy Dutch Auction: Auction not active: ",
+                auction_id
             );
+            return Err(ContractError::AuctionNotActive);
         }
 
-        // pay for the item
-        token.transfer(
-            &buyer,
-            &auction.seller,
-            &(auction
-                .item_info
-                .buy_now_price
-                .expect("Auction: Buy Now: Buy now price has not been set") as i128),
-        );
+        if buyer == auction.seller {
+            log!(&env, "Auction: Buy Dutch Auction: Seller cannot buy.");
+            return Err(ContractError::InvalidBidder);
+        }
 
-        let collection_client = collection::Client::new(&env, &auction.item_info.collection_addr);
+        let price = dutch_auction.price_at(env.ledger().timestamp());
 
-        collection_client.safe_transfer_from(
-            &env.current_contract_address(),
-            &auction.seller,
-            &buyer,
-            &auction.item_info.item_id,
-            &1,
-        );
+        distribute_funds(&env, Sale::auction(&env, &auction), &buyer, price)?;
+        deliver_items(&env, &auction, &buyer);
 
         auction.status = AuctionStatus::Ended;
-        auction.highest_bid = Some(
-            auction
-                .item_info
-                .buy_now_price
-                .expect("Auction: Buy Now: Buy now price has not been set"),
-        );
+        auction.highest_bid = Some(price);
 
         save_auction(&env, &auction)?;
 
         env.events()
-            .publish(("buy now", "auction id: "), auction_id);
-        env.events().publish(("buy now", "buyer: "), buyer);
+            .publish(("buy dutch auction", "auction id: "), auction_id);
+        env.events()
+            .publish(("buy dutch auction", "buyer: "), buyer);
+        env.events()
+            .publish(("buy dutch auction", "price: "), price);
 
-        Ok(())
+        Ok(price)
     }
 
     #[allow(dead_code)]
-    pub fn pause(env: Env, auction_id: u64) -> Result<(), ContractError> {
-        let mut auction = get_auction_by_id(&env, auction_id)?;
-        auction.seller.require_auth();
+    pub fn create_sealed_auction(
+        env: Env,
+        item_info: ItemInfo,
+        seller: Address,
+        auction_token: Option<Address>,
+        params: SealedAuctionParams,
+    ) -> Result<Auction, ContractError> {
+        let SealedAuctionParams {
+            commit_duration,
+            reveal_duration,
+            deposit,
+            pricing,
+        } = params;
 
-        if auction.status != AuctionStatus::Active {
+        validate_input_params(&env, &[&commit_duration, &reveal_duration, &deposit])?;
+
+        // the item is sold only after all bids have been revealed
+        if item_info.buy_now_price.is_some() {
             log!(
                 &env,
-                "Auction: Pause: Cannot pause inactive/ended auction: ",
-                auction_id
+                "Auction: Create Sealed Auction: Buy now price must not be set"
             );
-            return Err(ContractError::AuctionNotActive);
-        }
-
-        if env.ledger().timestamp() > auction.end_time {
-            log!(&env, "Auction: Pause: Auction expired: ", auction_id);
-            return Err(ContractError::AuctionNotActive);
+            return Err(ContractError::InvalidInputs);
         }
 
-        auction.status = AuctionStatus::Paused;
+        let auction = Self::create_auction(
+            env.clone(),
+            item_info,
+            seller,
+            commit_duration + reveal_duration,
+            auction_token,
+            None,
+        )?;
 
-        save_auction(&env, &auction)?;
+        let sealed_auction = SealedAuctionInfo {
+            commit_end: auction.end_time - reveal_duration,
+            deposit,
+            pricing,
+        };
+        save_sealed_auction(&env, auction.id, &sealed_auction);
 
-        env.events().publish(("pause", "auction id: "), auction_id);
+        env.events()
+            .publish(("create sealed auction", "auction id: "), auction.id);
+        env.events()
+            .publish(("create sealed auction", "phases: "), sealed_auction);
 
-        Ok(())
+        Ok(auction)
     }
 
+    /// Creates an auction that only the bidders allowed by `restriction` can bid on or buy now.
     #[allow(dead_code)]
-    pub fn unpause(env: &Env, auction_id: u64) -> Result<(), ContractError> {
-        let mut auction = get_auction_by_id(env, auction_id)?;
-        auction.seller.require_auth();
-
-        if auction.status != AuctionStatus::Paused {
-            log!(
-                &env,
-                "Auction: Unpause: Cannot activate unpaused auction: ",
-                auction_id
-            );
-            return Err(ContractError::AuctionNotPaused);
-        }
-
-        if env.ledger().timestamp() > auction.end_time {
-            log!(env, "Auction: Unpause: Auction expired: ", auction_id);
-            return Err(ContractError::AuctionNotActive);
+    pub fn create_private_auction(
+        env: Env,
+        item_info: ItemInfo,
+        seller: Address,
+        duration: u64,
+        auction_token: Option<Address>,
+        start_time: Option<u64>,
+        restriction: BidderRestriction,
+    ) -> Result<Auction, ContractError> {
+        match &restriction {
+            BidderRestriction::Allowlist(bidders) if bidders.is_empty() => {
+                log!(
+                    &env,
+                    "Auction: Create Private Auction: Allowlist must not be empty"
+                );
+                return Err(ContractError::InvalidInputs);
+            }
+            BidderRestriction::TokenGate(gate) => {
+                validate_input_params(&env, &[&gate.item_id, &gate.min_balance])?;
+            }
+            _ => {}
         }
 
-        auction.status = AuctionStatus::Active;
+        let auction = Self::create_auction(
+            env.clone(),
+            item_info,
+            seller,
+            duration,
+            auction_token,
+            start_time,
+        )?;
 
-        save_auction(env, &auction)?;
+        save_bidder_restriction(&env, auction.id, &restriction);
 
         env.events()
-            .publish(("unpause", "auction id: "), auction_id);
+            .publish(("create private auction", "auction id: "), auction.id);
+        env.events()
+            .publish(("create private auction", "restriction: "), restriction);
 
-        Ok(())
+        Ok(auction)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_bidder_restriction(env: Env, auction_id: u64) -> Option<BidderRestriction> {
+        get_bidder_restriction(&env, auction_id)
+    }
+
+    /// Auctions several items, possibly from different collections, as a single lot. All items
+    /// are escrowed on creation and delivered together when the auction is settled.
+    #[allow(dead_code)]
+    pub fn create_bundle_auction(
+        env: Env,
+        items: Vec<BundleItem>,
+        seller: Address,
+        duration: u64,
+        auction_token: Option<Address>,
+        minimum_price: Option<u64>,
+        buy_now_price: Option<u64>,
+    ) -> Result<Auction, ContractError> {
+        if items.len() < 2 || items.len() > MAX_BUNDLE_SIZE {
+            log!(
+                &env,
+                "Auction: Create Bundle Auction: Invalid number of items: ",
+                items.len()
+            );
+            return Err(ContractError::InvalidInputs);
+        }
+
+        for (index, item) in items.iter().enumerate() {
+            validate_input_params(&env, &[&item.item_id, &item.amount])?;
+            ensure_allowed_seller(&env, &seller, &item.collection_addr)?;
+            ensure_verified_collection(&env, &item.collection_addr)?;
+
+            if items.iter().skip(index + 1).any(|other| {
+                other.collection_addr == item.collection_addr && other.item_id == item.item_id
+            }) {
+                log!(
+                    &env,
+                    "Auction: Create Bundle Auction: Duplicated item: ",
+                    item.item_id
+                );
+                return Err(ContractError::InvalidInputs);
+            }
+        }
+
+        // the first item goes through the regular auction creation, which escrows it
+        let first = items.first_unchecked();
+        let item_info = ItemInfo {
+            collection_addr: first.collection_addr,
+            item_id: first.item_id,
+            minimum_price,
+            buy_now_price,
+            amount: first.amount,
+        };
+        let auction = Self::create_auction(
+            env.clone(),
+            item_info,
+            seller.clone(),
+            duration,
+            auction_token,
+            None,
+        )?;
+
+        for item in items.iter().skip(1) {
+            let nft_client = collection::Client::new(&env, &item.collection_addr);
+
+            if nft_client.balance_of(&seller, &item.item_id) < item.amount {
+                log!(
+                    &env,
+                    "Auction: Create Bundle Auction: Not enough balance of the item to sell: ",
+                    item.item_id
+                );
+                return Err(ContractError::NotEnoughBalance);
+            }
+
+            nft_client.set_approval_for_transfer(
+                &seller,
+                &env.current_contract_address(),
+                &item.item_id,
+                &true,
+            );
+            nft_client.safe_transfer_from(
+                &env.current_contract_address(),
+                &seller,
+                &env.current_contract_address(),
+                &item.item_id,
+                &item.amount,
+            );
+
+            revoke_approval_if_unlisted(&env, &seller, &item.collection_addr, item.item_id)?;
+        }
+
+        save_bundle_items(&env, auction.id, &items);
+
+        env.events()
+            .publish(("create bundle auction", "auction id: "), auction.id);
+        env.events()
+            .publish(("create bundle auction", "items: "), items);
+
+        Ok(auction)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_auction_items(env: Env, auction_id: u64) -> Result<Vec<BundleItem>, ContractError> {
+        let auction = get_auction_by_id(&env, auction_id)?;
+
+        Ok(auction_items(&env, &auction))
+    }
+
+    /// Commits to a sealed bid during the commit phase. `commitment` is the `sha256` hash of the
+    /// auction id as big-endian `u64`, the XDR encoded bidder address, the bid amount as
+    /// big-endian `u64` and a 32 bytes salt chosen by the bidder. The auction deposit is escrowed
+    /// with the commitment.
+    #[allow(dead_code)]
+    pub fn commit_bid(
+        env: Env,
+        auction_id: u64,
+        bidder: Address,
+        commitment: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        bidder.require_auth();
+        ensure_marketplace_active(&env)?;
+
+        let auction = get_auction_by_id(&env, auction_id)?;
+        let sealed_auction = get_sealed_auction(&env, auction_id).ok_or_else(|| {
+            log!(
+                &env,
+                "Auction: Commit Bid: Not a sealed-bid auction: ",
+                auction_id
+            );
+            ContractError::InvalidAuctionType
+        })?;
+
+        if auction.status != AuctionStatus::Active {
+            log!(
+                &env,
+                "Auction: Commit Bid: Auction not active: ",
+                auction_id
+            );
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        if env.ledger().timestamp() > sealed_auction.commit_end {
+            log!(&env, "Auction: Commit Bid: Commit phase is over");
+            return Err(ContractError::InvalidAuctionPhase);
+        }
+
+        if bidder == auction.seller {
+            log!(&env, "Auction: Commit Bid: Seller cannot place bids.");
+            return Err(ContractError::InvalidBidder);
+        }
+
+        if get_sealed_bid(&env, auction_id, &bidder).is_some() {
+            log!(&env, "Auction: Commit Bid: Bidder already committed a bid");
+            return Err(ContractError::BidAlreadyCommitted);
+        }
+
+        token::Client::new(&env, &auction.auction_token).transfer(
+            &bidder,
+            &env.current_contract_address(),
+            &(sealed_auction.deposit as i128),
+        );
+
+        save_sealed_bid(
+            &env,
+            auction_id,
+            &bidder,
+            &SealedBid {
+                commitment,
+                escrowed: sealed_auction.deposit,
+                revealed_bid: None,
+            },
+        );
+
+        env.events()
+            .publish(("commit bid", "auction id: "), auction_id);
+        env.events().publish(("commit bid", "bidder: "), bidder);
+
+        Ok(())
+    }
+
+    /// Reveals a committed bid during the reveal phase. When the bid exceeds the deposit, the
+    /// difference is escrowed as well.
+    #[allow(dead_code)]
+    pub fn reveal_bid(
+        env: Env,
+        auction_id: u64,
+        bidder: Address,
+        bid_amount: u64,
+        salt: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        bidder.require_auth();
+        ensure_marketplace_active(&env)?;
+
+        let auction = get_auction_by_id(&env, auction_id)?;
+        let sealed_auction = get_sealed_auction(&env, auction_id).ok_or_else(|| {
+            log!(
+                &env,
+                "Auction: Reveal Bid: Not a sealed-bid auction: ",
+                auction_id
+            );
+            ContractError::InvalidAuctionType
+        })?;
+
+        if auction.status != AuctionStatus::Active {
+            log!(
+                &env,
+                "Auction: Reveal Bid: Auction not active: ",
+                auction_id
+            );
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        let now = env.ledger().timestamp();
+        if now <= sealed_auction.commit_end || now > auction.end_time {
+            log!(&env, "Auction: Reveal Bid: Not in the reveal phase");
+            return Err(ContractError::InvalidAuctionPhase);
+        }
+
+        let mut sealed_bid = get_sealed_bid(&env, auction_id, &bidder).ok_or_else(|| {
+            log!(&env, "Auction: Reveal Bid: No committed bid found");
+            ContractError::NoBidFound
+        })?;
+
+        if sealed_bid.revealed_bid.is_some()
+            || bid_amount == 0
+            || sealed_bid_commitment(&env, auction_id, &bidder, bid_amount, salt)
+                != sealed_bid.commitment
+        {
+            log!(
+                &env,
+                "Auction: Reveal Bid: Revealed bid does not match the commitment"
+            );
+            return Err(ContractError::InvalidReveal);
+        }
+
+        if bid_amount > sealed_bid.escrowed {
+            token::Client::new(&env, &auction.auction_token).transfer(
+                &bidder,
+                &env.current_contract_address(),
+                &((bid_amount - sealed_bid.escrowed) as i128),
+            );
+            sealed_bid.escrowed = bid_amount;
+        }
+
+        sealed_bid.revealed_bid = Some(bid_amount);
+        record_bid(
+            &env,
+            &Bid {
+                auction_id,
+                bidder: bidder.clone(),
+                amount: bid_amount,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        save_sealed_bid(&env, auction_id, &bidder, &sealed_bid);
+
+        env.events()
+            .publish(("reveal bid", "auction id: "), auction_id);
+        env.events()
+            .publish(("reveal bid", "bidder: ", "bid: "), (bidder, bid_amount));
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn get_sealed_bid(
+        env: Env,
+        auction_id: u64,
+        bidder: Address,
+    ) -> Result<SealedBid, ContractError> {
+        get_sealed_bid(&env, auction_id, &bidder).ok_or_else(|| {
+            log!(&env, "Auction: Get Sealed Bid: No committed bid found");
+            ContractError::NoBidFound
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn place_bid(
+        env: Env,
+        auction_id: u64,
+        bidder: Address,
+        bid_amount: u64,
+    ) -> Result<(), ContractError> {
+        bidder.require_auth();
+        ensure_marketplace_active(&env)?;
+
+        let mut auction = get_started_auction(&env, auction_id)?;
+
+        if env.ledger().timestamp() > auction.end_time {
+            log!(&env, "Auction: Place Bid: Auction not active: ", auction_id);
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        if auction.status == AuctionStatus::Scheduled {
+            log!(
+                &env,
+                "Auction: Place Bid: Auction not started: ",
+                auction_id
+            );
+            return Err(ContractError::AuctionNotStarted);
+        }
+
+        if auction.status != AuctionStatus::Active {
+            log!(
+                &env,
+                "Auction: Place Bid: Trying to place a bid for inactive/cancelled auction with id: ", auction_id
+            );
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        if get_dutch_auction(&env, auction_id).is_some()
+            || get_sealed_auction(&env, auction_id).is_some()
+        {
+            log!(
+                &env,
+                "Auction: Place Bid: Only english auctions accept public bids: ",
+                auction_id
+            );
+            return Err(ContractError::InvalidAuctionType);
+        }
+
+        if bidder == auction.seller {
+            log!(&env, "Auction Place Bid: Seller cannot place bids.");
+            return Err(ContractError::InvalidBidder);
+        }
+
+        ensure_eligible_bidder(&env, auction_id, &bidder)?;
+
+        let min_next_bid = get_min_next_bid(&env, &auction)?;
+        if bid_amount < min_next_bid {
+            log!(
+                &env,
+                "Auction: Place Bid: Bid not enough. Amount bid: ",
+                bid_amount,
+                " minimum bid: ",
+                min_next_bid
+            );
+            return Err(match auction.highest_bid {
+                Some(highest_bid) if bid_amount > highest_bid => ContractError::BidIncrementNotMet,
+                _ => ContractError::BidNotEnough,
+            });
+        }
+
+        let token_client = token::Client::new(&env, &auction.auction_token);
+
+        match auction.highest_bid {
+            Some(current_highest_bid) if bid_amount > current_highest_bid => {
+                // refund the previous highest bidder
+                let old_bid_info = get_highest_bid(&env, auction_id)?;
+                credit_refund(
+                    &env,
+                    &old_bid_info.bidder,
+                    &auction.auction_token,
+                    old_bid_info.bid,
+                );
+            }
+            Some(_) => {
+                log!(
+                    &env,
+                    "Auction: Place Bid: Bid not enough. Amount bid: ",
+                    bid_amount
+                );
+                return Err(ContractError::BidNotEnough);
+            }
+            None => {}
+        };
+
+        token_client.transfer(
+            &bidder,
+            &env.current_contract_address(),
+            &(bid_amount as i128),
+        );
+
+        set_highest_bid(&env, auction_id, bid_amount, bidder.clone())?;
+        record_bid(
+            &env,
+            &Bid {
+                auction_id,
+                bidder: bidder.clone(),
+                amount: bid_amount,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+
+        auction.highest_bid = Some(bid_amount);
+        extend_auction_end_time(&env, &mut auction)?;
+        save_auction(&env, &auction)?;
+
+        env.events()
+            .publish(("place bid", "auction id"), auction_id);
+        env.events().publish(("place bid", "bidder"), bidder);
+        env.events().publish(("place bid", "bid"), bid_amount);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn finalize_auction(env: Env, auction_id: u64) -> Result<(), ContractError> {
+        ensure_marketplace_active(&env)?;
+
+        let mut auction = get_started_auction(&env, auction_id)?;
+
+        // Check if the auction can be finalized, a paused auction is settled once its time is up
+        // so that it cannot hold the item and the highest bid forever
+        if auction.status != AuctionStatus::Active && auction.status != AuctionStatus::Paused {
+            log!(
+                env,
+                "Auction: Finalize auction: Cannot finalize an inactive/ended auction."
+            );
+            return Err(ContractError::AuctionNotActive);
+        }
+        if env.ledger().timestamp() < auction.end_time {
+            log!(
+                env,
+                "Auction: Finalize auction: Auction cannot be ended early"
+            );
+            return Err(ContractError::AuctionNotFinished);
+        }
+
+        if let Some(sealed_auction) = get_sealed_auction(&env, auction_id) {
+            return settle_sealed_auction(&env, &mut auction, &sealed_auction);
+        }
+
+        let highest_bid = get_highest_bid(&env, auction_id)?;
+
+        let minimum_price_reached = auction.item_info.minimum_price.is_none_or(|min_price| {
+            auction
+                .highest_bid
+                .is_some_and(|highest_bid| highest_bid >= min_price)
+        });
+
+        // auctions without bids end without a sale, otherwise check if minimum price has been
+        // reached
+        if auction.highest_bid.is_none() {
+            auction.status = AuctionStatus::Ended;
+            save_auction(&env, &auction)?;
+            return_items(&env, &auction)?;
+
+            env.events().publish(("finalize auction", "no bids"), ());
+        } else if !minimum_price_reached {
+            credit_refund(
+                &env,
+                &highest_bid.bidder,
+                &auction.auction_token,
+                highest_bid.bid,
+            );
+            auction.status = AuctionStatus::Ended;
+            save_auction(&env, &auction)?;
+            return_items(&env, &auction)?;
+            log!(
+                env,
+                "Auction: Finalize auction: Miniminal price not reached"
+            );
+
+            env.events()
+                .publish(("finalize auction", "auction id: "), auction_id);
+            env.events()
+                .publish(("finalize auction", "highest bid: "), auction.highest_bid);
+            env.events().publish(
+                ("finalize auction", "minimum price: "),
+                auction.item_info.minimum_price,
+            );
+        } else if !can_deliver_items(&env, &auction) {
+            // the item of a non-escrowed auction is gone, the highest bidder gets the bid back
+            credit_refund(
+                &env,
+                &highest_bid.bidder,
+                &auction.auction_token,
+                highest_bid.bid,
+            );
+            auction.status = AuctionStatus::Ended;
+            save_auction(&env, &auction)?;
+            log!(
+                env,
+                "Auction: Finalize auction: Item can no longer be delivered"
+            );
+
+            env.events()
+                .publish(("finalize auction", "item not delivered"), auction_id);
+        } else {
+            distribute_funds(
+                &env,
+                Sale::auction(&env, &auction),
+                &env.current_contract_address(),
+                highest_bid.bid,
+            )?;
+            deliver_items(&env, &auction, &highest_bid.bidder);
+
+            auction.status = AuctionStatus::Ended;
+            save_auction(&env, &auction)?;
+            env.events()
+                .publish(("finalize auction", "highest bidder: "), highest_bid.bidder);
+            env.events()
+                .publish(("finalize auction", "highest bid: "), highest_bid.bid);
+        };
+
+        Ok(())
+    }
+
+    /// Buys `quantity` units of the auctioned item for the buy now price of each unit. The
+    /// auction stays active with the remaining units until all of them are sold. Bundles are sold
+    /// as a single lot, so their `quantity` must be 1.
+    #[allow(dead_code)]
+    pub fn buy_now(
+        env: Env,
+        auction_id: u64,
+        buyer: Address,
+        quantity: u64,
+    ) -> Result<(), ContractError> {
+        buyer.require_auth();
+        ensure_marketplace_active(&env)?;
+
+        let mut auction = get_started_auction(&env, auction_id)?;
+
+        if auction.status == AuctionStatus::Scheduled {
+            log!(&env, "Auction: Buy Now: Auction not started: ", auction_id);
+            return Err(ContractError::AuctionNotStarted);
+        }
+
+        if env.ledger().timestamp() > auction.end_time || auction.status != AuctionStatus::Active {
+            log!(&env, "Auction: Buy Now: Auction not active: ", auction_id);
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        let Some(unit_price) = auction.item_info.buy_now_price else {
+            log!(
+                env,
+                "Auction: Buy Now: trying to buy an item that does not allow `buy now`"
+            );
+            return Err(ContractError::NoBuyNowOption);
+        };
+
+        ensure_eligible_bidder(&env, auction_id, &buyer)?;
+
+        let available = if get_bundle_items(&env, auction_id).is_some() {
+            1
+        } else {
+            auction.item_info.amount
+        };
+
+        if quantity == 0 || quantity > available {
+            log!(
+                &env,
+                "Auction: Buy Now: Invalid quantity. Available: ",
+                available
+            );
+            return Err(ContractError::InvalidInputs);
+        }
+
+        let price = unit_price.checked_mul(quantity).ok_or_else(|| {
+            log!(&env, "Auction: Buy Now: Total price overflow");
+            ContractError::InvalidInputs
+        })?;
+
+        // bids are placed on the whole lot, so it can be bought in parts only while there are none
+        let partial = quantity < available;
+        if partial && auction.highest_bid.is_some() {
+            log!(
+                &env,
+                "Auction: Buy Now: Cannot buy part of a lot with a standing bid: ",
+                auction_id
+            );
+            return Err(ContractError::PartialBuyWithBids);
+        }
+
+        let old_highest_bid = get_highest_bid(&env, auction_id)?;
+
+        // refund only when there is some previous highest bid, as the lot it was placed on is sold
+        if old_highest_bid.bid > 0 {
+            credit_refund(
+                &env,
+                &old_highest_bid.bidder,
+                &auction.auction_token,
+                old_highest_bid.bid,
+            );
+        }
+
+        // pay for the item
+        distribute_funds(&env, Sale::auction(&env, &auction), &buyer, price)?;
+
+        if partial {
+            collection::Client::new(&env, &auction.item_info.collection_addr).safe_transfer_from(
+                &env.current_contract_address(),
+                &item_holder(&env, &auction),
+                &buyer,
+                &auction.item_info.item_id,
+                &quantity,
+            );
+
+            // the reserve price of the lot shrinks with it, rounding in favor of the seller
+            let remaining = available - quantity;
+            auction.item_info.minimum_price =
+                auction.item_info.minimum_price.map(|minimum_price| {
+                    (minimum_price as u128 * remaining as u128).div_ceil(available as u128) as u64
+                });
+            auction.item_info.amount = remaining;
+        } else {
+            deliver_items(&env, &auction, &buyer);
+
+            auction.status = AuctionStatus::Ended;
+            auction.highest_bid = Some(price);
+        }
+
+        save_auction(&env, &auction)?;
+
+        env.events()
+            .publish(("buy now", "auction id: "), auction_id);
+        env.events().publish(("buy now", "buyer: "), buyer);
+        env.events().publish(("buy now", "quantity: "), quantity);
+        env.events()
+            .publish(("buy now", "remaining: "), available - quantity);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn pause(env: Env, auction_id: u64) -> Result<(), ContractError> {
+        let mut auction = get_started_auction(&env, auction_id)?;
+        auction.seller.require_auth();
+
+        if auction.status != AuctionStatus::Active {
+            log!(
+                &env,
+                "Auction: Pause: Cannot pause inactive/ended auction: ",
+                auction_id
+            );
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        // a paused sealed auction could outlast its phases and lock the escrowed deposits
+        if get_sealed_auction(&env, auction_id).is_some() {
+            log!(
+                &env,
+                "Auction: Pause: Sealed-bid auctions cannot be paused: ",
+                auction_id
+            );
+            return Err(ContractError::InvalidAuctionType);
+        }
+
+        if env.ledger().timestamp() > auction.end_time {
+            log!(&env, "Auction: Pause: Auction expired: ", auction_id);
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        auction.status = AuctionStatus::Paused;
+
+        save_auction(&env, &auction)?;
+
+        env.events().publish(("pause", "auction id: "), auction_id);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn unpause(env: &Env, auction_id: u64) -> Result<(), ContractError> {
+        let mut auction = get_auction_by_id(env, auction_id)?;
+        auction.seller.require_auth();
+
+        if auction.status != AuctionStatus::Paused {
+            log!(
+                &env,
+                "Auction: Unpause: Cannot activate unpaused auction: ",
+                auction_id
+            );
+            return Err(ContractError::AuctionNotPaused);
+        }
+
+        if env.ledger().timestamp() > auction.end_time {
+            log!(env, "Auction: Unpause: Auction expired: ", auction_id);
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        auction.status = AuctionStatus::Active;
+
+        save_auction(env, &auction)?;
+
+        env.events()
+            .publish(("unpause", "auction id: "), auction_id);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn cancel_auction(env: Env, auction_id: u64) -> Result<(), ContractError> {
+        let mut auction = get_started_auction(&env, auction_id)?;
+        auction.seller.require_auth();
+
+        if auction.status != AuctionStatus::Active
+            && auction.status != AuctionStatus::Paused
+            && auction.status != AuctionStatus::Scheduled
+        {
+            log!(
+                &env,
+                "Auction: Cancel auction: Cannot cancel inactive/ended auction: ",
+                auction_id
+            );
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        // committed sealed bids are kept secret, so they cannot be judged against the policy
+        if !get_sealed_bidders(&env, auction_id).is_empty() {
+            log!(
+                &env,
+                "Auction: Cancel auction: Cannot cancel an auction with sealed bids: ",
+                auction_id
+            );
+            return Err(ContractError::AuctionCannotBeCancelled);
+        }
+
+        if auction.highest_bid.is_some() {
+            // once the auction is over the highest bidder has won the item
+            if !get_config(&env)?.allow_cancel_with_bids
+                || env.ledger().timestamp() > auction.end_time
+            {
+                log!(
+                    &env,
+                    "Auction: Cancel auction: Cannot cancel an auction with bids: ",
+                    auction_id
+                );
+                return Err(ContractError::AuctionCannotBeCancelled);
+            }
+
+            let highest_bid = get_highest_bid(&env, auction_id)?;
+            credit_refund(
+                &env,
+                &highest_bid.bidder,
+                &auction.auction_token,
+                highest_bid.bid,
+            );
+
+            env.events().publish(
+                ("cancel auction", "refunded bidder: "),
+                (highest_bid.bidder, highest_bid.bid),
+            );
+        }
+
+        auction.status = AuctionStatus::Cancelled;
+        save_auction(&env, &auction)?;
+
+        return_items(&env, &auction)?;
+
+        env.events()
+            .publish(("cancel auction", "auction id: "), auction_id);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn set_bid_increment(
+        env: Env,
+        auction_id: u64,
+        bid_increment: BidIncrement,
+    ) -> Result<(), ContractError> {
+        let auction = get_started_auction(&env, auction_id)?;
+        auction.seller.require_auth();
+
+        if auction.status != AuctionStatus::Active
+            && auction.status != AuctionStatus::Paused
+            && auction.status != AuctionStatus::Scheduled
+        {
+            log!(
+                &env,
+                "Auction: Set bid increment: Auction not active: ",
+                auction_id
+            );
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        // the rules cannot change once bidders have committed funds
+        if auction.highest_bid.is_some() {
+            log!(
+                &env,
+                "Auction: Set bid increment: Auction already has bids: ",
+                auction_id
+            );
+            return Err(ContractError::InvalidInputs);
+        }
+
+        validate_bps(&env, bid_increment.bps)?;
+        set_bid_increment(&env, auction_id, &bid_increment);
+
+        env.events()
+            .publish(("set bid increment", "auction id: "), auction_id);
+        env.events()
+            .publish(("set bid increment", "bid increment: "), bid_increment);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn get_min_next_bid(env: Env, auction_id: u64) -> Result<u64, ContractError> {
+        let auction = get_auction_by_id(&env, auction_id)?;
+
+        get_min_next_bid(&env, &auction)
+    }
+
+    #[allow(dead_code)]
+    pub fn list_item(
+        env: Env,
+        seller: Address,
+        collection_addr: Address,
+        item_id: u64,
+        amount: u64,
+        price: u64,
+        payment_token: Address,
+    ) -> Result<Listing, ContractError> {
+        seller.require_auth();
+        ensure_marketplace_active(&env)?;
+
+        validate_input_params(&env, &[&item_id, &amount, &price])?;
+        ensure_allowed_seller(&env, &seller, &collection_addr)?;
+        ensure_verified_collection(&env, &collection_addr)?;
+
+        let nft_client = collection::Client::new(&env, &collection_addr);
+        if nft_client.balance_of(&seller, &item_id) < amount {
+            log!(
+                &env,
+                "Auction: List Item: Not enough balance of the item to sell"
+            );
+            return Err(ContractError::NotEnoughBalance);
+        }
+
+        nft_client.set_approval_for_transfer(
+            &seller,
+            &env.current_contract_address(),
+            &item_id,
+            &true,
+        );
+
+        let listing = Listing {
+            id: generate_listing_id(&env),
+            collection_addr,
+            item_id,
+            seller: seller.clone(),
+            amount,
+            price,
+            payment_token,
+            status: ListingStatus::Active,
+        };
+        save_listing(&env, &listing);
+
+        env.events()
+            .publish(("list item", "listing id: "), listing.id);
+        env.events().publish(("list item", "seller: "), seller);
+        env.events()
+            .publish(("list item", "price: "), (listing.amount, listing.price));
+
+        Ok(listing)
+    }
+
+    #[allow(dead_code)]
+    pub fn update_listing(
+        env: Env,
+        listing_id: u64,
+        amount: Option<u64>,
+        price: Option<u64>,
+    ) -> Result<Listing, ContractError> {
+        let mut listing = get_listing_by_id(&env, listing_id)?;
+        listing.seller.require_auth();
+
+        if listing.status != ListingStatus::Active {
+            log!(
+                &env,
+                "Auction: Update Listing: Listing not active: ",
+                listing_id
+            );
+            return Err(ContractError::ListingNotActive);
+        }
+
+        if let Some(amount) = amount {
+            validate_input_params(&env, &[&amount])?;
+
+            let nft_client = collection::Client::new(&env, &listing.collection_addr);
+            if nft_client.balance_of(&listing.seller, &listing.item_id) < amount {
+                log!(
+                    &env,
+                    "Auction: Update Listing: Not enough balance of the item to sell"
+                );
+                return Err(ContractError::NotEnoughBalance);
+            }
+
+            listing.amount = amount;
+        }
+
+        if let Some(price) = price {
+            validate_input_params(&env, &[&price])?;
+            listing.price = price;
+        }
+
+        save_listing(&env, &listing);
+
+        env.events()
+            .publish(("update listing", "listing id: "), listing_id);
+        env.events().publish(
+            ("update listing", "price: "),
+            (listing.amount, listing.price),
+        );
+
+        Ok(listing)
+    }
+
+    #[allow(dead_code)]
+    pub fn delist(env: Env, listing_id: u64) -> Result<(), ContractError> {
+        let mut listing = get_listing_by_id(&env, listing_id)?;
+        listing.seller.require_auth();
+
+        if listing.status != ListingStatus::Active {
+            log!(&env, "Auction: Delist: Listing not active: ", listing_id);
+            return Err(ContractError::ListingNotActive);
+        }
+
+        listing.status = ListingStatus::Delisted;
+        save_listing(&env, &listing);
+
+        revoke_approval_if_unlisted(
+            &env,
+            &listing.seller,
+            &listing.collection_addr,
+            listing.item_id,
+        )?;
+
+        env.events().publish(("delist", "listing id: "), listing_id);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn buy_listing(
+        env: Env,
+        listing_id: u64,
+        buyer: Address,
+        quantity: u64,
+    ) -> Result<Listing, ContractError> {
+        buyer.require_auth();
+        ensure_marketplace_active(&env)?;
+
+        let mut listing = get_listing_by_id(&env, listing_id)?;
+
+        if listing.status != ListingStatus::Active {
+            log!(
+                &env,
+                "Auction: Buy Listing: Listing not active: ",
+                listing_id
+            );
+            return Err(ContractError::ListingNotActive);
+        }
+
+        if buyer == listing.seller {
+            log!(
+                &env,
+                "Auction: Buy Listing: Seller cannot buy their own item"
+            );
+            return Err(ContractError::InvalidBidder);
+        }
+
+        // listings made before the seller or the collection got blocked cannot be sold either
+        ensure_allowed_seller(&env, &listing.seller, &listing.collection_addr)?;
+
+        if quantity == 0 || quantity > listing.amount {
+            log!(
+                &env,
+                "Auction: Buy Listing: Invalid quantity. Available: ",
+                listing.amount
+            );
+            return Err(ContractError::InvalidInputs);
+        }
+
+        let total_price = listing.price.checked_mul(quantity).ok_or_else(|| {
+            log!(&env, "Auction: Buy Listing: Total price overflow");
+            ContractError::InvalidInputs
+        })?;
+
+        distribute_funds(&env, Sale::listing(&env, &listing), &buyer, total_price)?;
+
+        collection::Client::new(&env, &listing.collection_addr).safe_transfer_from(
+            &env.current_contract_address(),
+            &listing.seller,
+            &buyer,
+            &listing.item_id,
+            &quantity,
+        );
+
+        listing.amount -= quantity;
+        if listing.amount == 0 {
+            listing.status = ListingStatus::Sold;
+        }
+        save_listing(&env, &listing);
+
+        if listing.status == ListingStatus::Sold {
+            revoke_approval_if_unlisted(
+                &env,
+                &listing.seller,
+                &listing.collection_addr,
+                listing.item_id,
+            )?;
+        }
+
+        env.events()
+            .publish(("buy listing", "listing id: "), listing_id);
+        env.events()
+            .publish(("buy listing", "buyer: "), (buyer, quantity, total_price));
+
+        Ok(listing)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_listing(env: Env, listing_id: u64) -> Result<Listing, ContractError> {
+        get_listing_by_id(&env, listing_id)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_active_listings(
+        env: Env,
+        start_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Listing>, ContractError> {
+        let mut active_listings = vec![&env];
+
+        for listing in get_listings(&env, start_index, limit)?.iter() {
+            if listing.status == ListingStatus::Active {
+                active_listings.push_back(listing);
+            }
+        }
+
+        Ok(active_listings)
+    }
+
+    /// Returns up to `limit` listings of `seller` in creation order, starting at `start_index`,
+    /// 0 being the oldest listing.
+    #[allow(dead_code)]
+    pub fn get_listings_by_seller(
+        env: Env,
+        seller: Address,
+        start_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Listing>, ContractError> {
+        get_listings_by_seller(&env, &seller, start_index, limit)
+    }
+
+    #[allow(dead_code)]
+    pub fn make_offer(
+        env: Env,
+        buyer: Address,
+        item: OfferItem,
+        price: u64,
+        payment_token: Address,
+        expiration: u64,
+    ) -> Result<Offer, ContractError> {
+        buyer.require_auth();
+        ensure_marketplace_active(&env)?;
+
+        validate_input_params(&env, &[&item.item_id.unwrap_or(1), &item.amount, &price])?;
+
+        if expiration <= env.ledger().timestamp() {
+            log!(
+                &env,
+                "Auction: Make Offer: Expiration must be in the future"
+            );
+            return Err(ContractError::InvalidInputs);
+        }
+
+        token::Client::new(&env, &payment_token).transfer(
+            &buyer,
+            &env.current_contract_address(),
+            &(price as i128),
+        );
+
+        let offer = Offer {
+            id: generate_offer_id(&env),
+            buyer: buyer.clone(),
+            item,
+            price,
+            payment_token,
+            expiration,
+            status: OfferStatus::Active,
+        };
+        save_offer(&env, &offer);
+
+        env.events().publish(("make offer", "offer id: "), offer.id);
+        env.events().publish(("make offer", "buyer: "), buyer);
+        env.events()
+            .publish(("make offer", "item: "), offer.item.clone());
+
+        Ok(offer)
+    }
+
+    /// Cancels an active offer and refunds the escrowed payment. Expired offers are reclaimed
+    /// the same way.
+    #[allow(dead_code)]
+    pub fn cancel_offer(env: Env, offer_id: u64) -> Result<(), ContractError> {
+        let mut offer = get_offer_by_id(&env, offer_id)?;
+        offer.buyer.require_auth();
+
+        if offer.status != OfferStatus::Active {
+            log!(&env, "Auction: Cancel Offer: Offer not active: ", offer_id);
+            return Err(ContractError::OfferNotActive);
+        }
+
+        offer.status = OfferStatus::Cancelled;
+        save_offer(&env, &offer);
+
+        token::Client::new(&env, &offer.payment_token).transfer(
+            &env.current_contract_address(),
+            &offer.buyer,
+            &(offer.price as i128),
+        );
+
+        env.events()
+            .publish(("cancel offer", "offer id: "), offer_id);
+
+        Ok(())
+    }
+
+    /// Sells `item_id` to the author of the offer. `item_id` must match the offer, unless the
+    /// offer accepts any item of the collection.
+    #[allow(dead_code)]
+    pub fn accept_offer(
+        env: Env,
+        offer_id: u64,
+        seller: Address,
+        item_id: u64,
+    ) -> Result<(), ContractError> {
+        seller.require_auth();
+        ensure_marketplace_active(&env)?;
+
+        let mut offer = get_offer_by_id(&env, offer_id)?;
+
+        if offer.status != OfferStatus::Active {
+            log!(&env, "Auction: Accept Offer: Offer not active: ", offer_id);
+            return Err(ContractError::OfferNotActive);
+        }
+
+        if env.ledger().timestamp() > offer.expiration {
+            log!(&env, "Auction: Accept Offer: Offer expired: ", offer_id);
+            return Err(ContractError::OfferExpired);
+        }
+
+        if offer
+            .item
+            .item_id
+            .is_some_and(|offered_item| offered_item != item_id)
+        {
+            log!(
+                &env,
+                "Auction: Accept Offer: Offer was made for another item"
+            );
+            return Err(ContractError::InvalidInputs);
+        }
+
+        if seller == offer.buyer {
+            log!(&env, "Auction: Accept Offer: Buyer cannot accept own offer");
+            return Err(ContractError::InvalidBidder);
+        }
+
+        ensure_allowed_seller(&env, &seller, &offer.item.collection_addr)?;
+
+        let nft_client = collection::Client::new(&env, &offer.item.collection_addr);
+        if nft_client.balance_of(&seller, &item_id) < offer.item.amount {
+            log!(
+                &env,
+                "Auction: Accept Offer: Not enough balance of the item to sell"
+            );
+            return Err(ContractError::NotEnoughBalance);
+        }
+
+        offer.status = OfferStatus::Accepted;
+        save_offer(&env, &offer);
+
+        nft_client.set_approval_for_transfer(
+            &seller,
+            &env.current_contract_address(),
+            &item_id,
+            &true,
+        );
+        nft_client.safe_transfer_from(
+            &env.current_contract_address(),
+            &seller,
+            &offer.buyer,
+            &item_id,
+            &offer.item.amount,
+        );
+        revoke_approval_if_unlisted(&env, &seller, &offer.item.collection_addr, item_id)?;
+
+        distribute_funds(
+            &env,
+            Sale {
+                kind: "offer id: ",
+                id: offer.id,
+                payment_token: &offer.payment_token,
+                items: vec![
+                    &env,
+                    BundleItem {
+                        collection_addr: offer.item.collection_addr.clone(),
+                        item_id,
+                        amount: offer.item.amount,
+                    },
+                ],
+                seller: &seller,
+            },
+            &env.current_contract_address(),
+            offer.price,
+        )?;
+
+        env.events()
+            .publish(("accept offer", "offer id: "), offer_id);
+        env.events()
+            .publish(("accept offer", "seller: "), (seller, item_id));
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn get_offer(env: Env, offer_id: u64) -> Result<Offer, ContractError> {
+        get_offer_by_id(&env, offer_id)
+    }
+
+    /// Returns up to `limit` offers of `buyer` in creation order, starting at `start_index`, 0
+    /// being the oldest offer.
+    #[allow(dead_code)]
+    pub fn get_offers_by_buyer(
+        env: Env,
+        buyer: Address,
+        start_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Offer>, ContractError> {
+        get_offers_by_buyer(&env, &buyer, start_index, limit)
+    }
+
+    /// Returns the offers for items of the collection that can still be accepted, including
+    /// collection-wide offers. `start_index` and `limit` select a page among all the offers ever
+    /// made for the collection, 0 being the oldest, so a page can hold fewer than `limit` offers.
+    #[allow(dead_code)]
+    pub fn get_active_offers_by_collection(
+        env: Env,
+        collection_addr: Address,
+        start_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Offer>, ContractError> {
+        let now = env.ledger().timestamp();
+        let mut active_offers = vec![&env];
+
+        for offer in get_offers_by_collection(&env, &collection_addr, start_index, limit)?.iter() {
+            if offer.status == OfferStatus::Active && offer.expiration >= now {
+                active_offers.push_back(offer);
+            }
+        }
+
+        Ok(active_offers)
     }
 
     #[allow(dead_code)]
     pub fn get_auction(env: Env, auction_id: u64) -> Result<Auction, ContractError> {
-        let auction = get_auction_by_id(&env, auction_id)?;
+        let auction = get_current_auction(&env, auction_id)?;
+
+        Ok(auction)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_active_auctions(
+        env: Env,
+        start_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Auction>, ContractError> {
+        let all_auctions = get_auctions(&env, start_index, limit)?;
+
+        let mut filtered_auctions = vec![&env];
+
+        for auction in all_auctions.iter() {
+            if auction.status == AuctionStatus::Active {
+                filtered_auctions.push_back(auction);
+            }
+        }
+
+        Ok(filtered_auctions)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_auctions_by_seller(
+        env: Env,
+        seller: Address,
+        cursor: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<AuctionPage, ContractError> {
+        get_auctions_by_seller(&env, &seller, cursor, limit)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_auctions_by_collection(
+        env: Env,
+        collection_addr: Address,
+        cursor: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<AuctionPage, ContractError> {
+        get_auctions_by_collection(&env, &collection_addr, cursor, limit)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_auctions_by_item(
+        env: Env,
+        collection_addr: Address,
+        item_id: u64,
+        cursor: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<AuctionPage, ContractError> {
+        get_auctions_by_item(&env, &collection_addr, item_id, cursor, limit)
+    }
+
+    /// Returns the auctions in which `bidder` currently holds the highest bid.
+    #[allow(dead_code)]
+    pub fn get_auctions_by_highest_bidder(
+        env: Env,
+        bidder: Address,
+        cursor: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<AuctionPage, ContractError> {
+        get_auctions_by_highest_bidder(&env, &bidder, cursor, limit)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_auctions_by_status(
+        env: Env,
+        status: AuctionStatus,
+        cursor: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<AuctionPage, ContractError> {
+        get_auctions_by_status(&env, status, cursor, limit)
+    }
+
+    /// Returns the active auctions that end next, soonest first.
+    #[allow(dead_code)]
+    pub fn get_ending_soon_auctions(
+        env: Env,
+        cursor: Option<u128>,
+        limit: Option<u64>,
+    ) -> Result<TimedAuctionPage, ContractError> {
+        get_ending_soon_auctions(&env, cursor, limit)
+    }
+
+    /// Returns the scheduled auctions that start next, soonest first.
+    #[allow(dead_code)]
+    pub fn get_upcoming_auctions(
+        env: Env,
+        cursor: Option<u128>,
+        limit: Option<u64>,
+    ) -> Result<TimedAuctionPage, ContractError> {
+        get_upcoming_auctions(&env, cursor, limit)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_highest_bid(env: Env, auction_id: u64) -> Result<HighestBid, ContractError> {
+        let highest_bid_info = get_highest_bid(&env, auction_id)?;
+
+        Ok(highest_bid_info)
+    }
+
+    /// Returns the accepted bids of an auction, oldest first, starting at `start_index` (0 by
+    /// default).
+    #[allow(dead_code)]
+    pub fn get_bids(
+        env: Env,
+        auction_id: u64,
+        start_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Bid>, ContractError> {
+        get_auction_by_id(&env, auction_id)?;
+
+        Ok(get_bids(&env, auction_id, start_index, limit))
+    }
+
+    /// Returns the bids placed by `bidder` across all auctions, oldest first.
+    #[allow(dead_code)]
+    pub fn get_bids_by_bidder(
+        env: Env,
+        bidder: Address,
+        start_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<Bid> {
+        get_bids_by_bidder(&env, &bidder, start_index, limit)
+    }
+
+    /// Transfers everything credited to `account` in `token` and returns the withdrawn amount.
+    /// Bidders are credited their refunds, sellers their sale proceeds and creators their
+    /// royalties.
+    #[allow(dead_code)]
+    pub fn withdraw_refund(
+        env: Env,
+        account: Address,
+        token: Address,
+    ) -> Result<u128, ContractError> {
+        account.require_auth();
+
+        let amount = get_pending_refund(&env, &account, &token);
+        if amount == 0 {
+            log!(&env, "Auction: Withdraw Refund: Nothing to withdraw");
+            return Err(ContractError::NoRefundPending);
+        }
+
+        set_pending_refund(&env, &account, &token, 0);
+
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &account,
+            &(amount as i128),
+        );
+
+        env.events()
+            .publish(("withdraw refund", "account: "), (account, token, amount));
+
+        Ok(amount)
+    }
+
+    #[allow(dead_code)]
+    pub fn pending_refund(env: Env, account: Address, token: Address) -> u128 {
+        get_pending_refund(&env, &account, &token)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_config(env: Env) -> Result<Config, ContractError> {
+        get_config(&env)
+    }
+
+    #[allow(dead_code)]
+    pub fn update_config(
+        env: Env,
+        max_royalty_bps: Option<u32>,
+        protocol_fee_bps: Option<u32>,
+        treasury: Option<Address>,
+        allow_cancel_with_bids: Option<bool>,
+        anti_sniping: Option<AntiSnipingConfig>,
+        min_bid_increment: Option<BidIncrement>,
+    ) -> Result<Config, ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        let mut config = get_config(&env)?;
+
+        if let Some(max_royalty_bps) = max_royalty_bps {
+            config.max_royalty_bps = max_royalty_bps;
+            env.events()
+                .publish(("update config", "max royalty bps: "), max_royalty_bps);
+        }
+
+        if let Some(protocol_fee_bps) = protocol_fee_bps {
+            config.protocol_fee_bps = protocol_fee_bps;
+            env.events()
+                .publish(("update config", "protocol fee bps: "), protocol_fee_bps);
+        }
+
+        if let Some(treasury) = treasury {
+            config.treasury = treasury.clone();
+            env.events()
+                .publish(("update config", "treasury: "), treasury);
+        }
+
+        if let Some(allow_cancel_with_bids) = allow_cancel_with_bids {
+            config.allow_cancel_with_bids = allow_cancel_with_bids;
+            env.events().publish(
+                ("update config", "allow cancel with bids: "),
+                allow_cancel_with_bids,
+            );
+        }
+
+        if let Some(anti_sniping) = anti_sniping {
+            config.anti_sniping = anti_sniping.clone();
+            env.events()
+                .publish(("update config", "anti sniping: "), anti_sniping);
+        }
+
+        if let Some(min_bid_increment) = min_bid_increment {
+            validate_bps(&env, min_bid_increment.bps)?;
+            config.min_bid_increment = min_bid_increment.clone();
+            env.events()
+                .publish(("update config", "min bid increment: "), min_bid_increment);
+        }
+
+        validate_bps(
+            &env,
+            config
+                .max_royalty_bps
+                .saturating_add(config.protocol_fee_bps),
+        )?;
+
+        save_config(&env, config.clone());
+
+        Ok(config)
+    }
+
+    /// Adds `token` to the tokens accepted as payment for auctions, or updates its creation fee
+    /// and minimum price when already accepted.
+    #[allow(dead_code)]
+    pub fn add_accepted_token(
+        env: Env,
+        token: Address,
+        creation_fee: u128,
+        min_price: u64,
+    ) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        let accepted_token = AcceptedToken {
+            token: token.clone(),
+            creation_fee,
+            min_price,
+        };
+
+        let mut accepted_tokens = get_accepted_tokens(&env);
+        match accepted_tokens
+            .iter()
+            .position(|accepted_token| accepted_token.token == token)
+        {
+            Some(idx) => accepted_tokens.set(idx as u32, accepted_token.clone()),
+            None => accepted_tokens.push_back(accepted_token.clone()),
+        };
+        save_accepted_tokens(&env, &accepted_tokens);
+
+        env.events()
+            .publish(("add accepted token", "token: "), accepted_token);
+
+        Ok(())
+    }
+
+    /// Stops accepting `token` for new auctions. Existing auctions are not affected.
+    #[allow(dead_code)]
+    pub fn remove_accepted_token(env: Env, token: Address) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        let mut accepted_tokens = get_accepted_tokens(&env);
+        let idx = accepted_tokens
+            .iter()
+            .position(|accepted_token| accepted_token.token == token)
+            .ok_or_else(|| {
+                log!(&env, "Auction: Remove accepted token: Token not accepted");
+                ContractError::TokenNotAccepted
+            })?;
+        accepted_tokens.remove(idx as u32);
+        save_accepted_tokens(&env, &accepted_tokens);
+
+        env.events()
+            .publish(("remove accepted token", "token: "), token);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn get_accepted_tokens(env: Env) -> Vec<AcceptedToken> {
+        get_accepted_tokens(&env)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_accrued_fees(env: Env, token: Address) -> u128 {
+        get_accrued_fees(&env, &token)
+    }
+
+    #[allow(dead_code)]
+    pub fn withdraw_fees(env: Env, token: Address, amount: u128) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        let accrued_fees = get_accrued_fees(&env, &token);
+        if amount > accrued_fees {
+            log!(
+                &env,
+                "Auction: Withdraw fees: Not enough accrued fees. Available: ",
+                accrued_fees
+            );
+            return Err(ContractError::NotEnoughAccruedFees);
+        }
+
+        let treasury = get_config(&env)?.treasury;
+
+        save_accrued_fees(&env, &token, accrued_fees - amount);
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &treasury,
+            &(amount as i128),
+        );
+
+        env.events().publish(("withdraw fees", "token: "), token);
+        env.events()
+            .publish(("withdraw fees", "treasury: "), treasury);
+        env.events().publish(("withdraw fees", "amount: "), amount);
+
+        Ok(())
+    }
+
+    /// Halts auction creation, bidding and settlement, as well as trading of listings and offers,
+    /// across the whole marketplace. Refunds, cancellations and delisting remain available.
+    #[allow(dead_code)]
+    pub fn pause_marketplace(env: Env) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        set_marketplace_paused(&env, true);
+
+        env.events()
+            .publish(("pause marketplace", "admin: "), admin);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn unpause_marketplace(env: Env) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        set_marketplace_paused(&env, false);
+
+        env.events()
+            .publish(("unpause marketplace", "admin: "), admin);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn is_marketplace_paused(env: Env) -> bool {
+        is_marketplace_paused(&env)
+    }
+
+    /// Takes down an unsettled auction. The highest bid and any sealed bid deposits are credited
+    /// back as refunds and the escrowed items are returned to the seller.
+    #[allow(dead_code)]
+    pub fn delist_auction(env: Env, auction_id: u64) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        let mut auction = get_auction_by_id(&env, auction_id)?;
+
+        if auction.status != AuctionStatus::Active
+            && auction.status != AuctionStatus::Paused
+            && auction.status != AuctionStatus::Scheduled
+        {
+            log!(
+                &env,
+                "Auction: Delist auction: Cannot delist inactive/ended auction: ",
+                auction_id
+            );
+            return Err(ContractError::AuctionNotActive);
+        }
+
+        if auction.highest_bid.is_some() {
+            let highest_bid = get_highest_bid(&env, auction_id)?;
+            credit_refund(
+                &env,
+                &highest_bid.bidder,
+                &auction.auction_token,
+                highest_bid.bid,
+            );
+
+            env.events().publish(
+                ("delist auction", "refunded bidder: "),
+                (highest_bid.bidder, highest_bid.bid),
+            );
+        }
+
+        for bidder in get_sealed_bidders(&env, auction_id).iter() {
+            if let Some(sealed_bid) = get_sealed_bid(&env, auction_id, &bidder) {
+                credit_refund(&env, &bidder, &auction.auction_token, sealed_bid.escrowed);
+
+                env.events().publish(
+                    ("delist auction", "refunded bidder: "),
+                    (bidder, sealed_bid.escrowed),
+                );
+            }
+        }
+
+        auction.status = AuctionStatus::Cancelled;
+        save_auction(&env, &auction)?;
+
+        return_items(&env, &auction)?;
+
+        env.events()
+            .publish(("delist auction", "auction id: "), auction_id);
+
+        Ok(())
+    }
+
+    /// Prevents new auctions of items from `collection`. Existing auctions are not affected.
+    #[allow(dead_code)]
+    pub fn block_collection(env: Env, collection: Address) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        set_collection_blocked(&env, &collection, true);
+
+        env.events()
+            .publish(("block collection", "collection: "), collection);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn unblock_collection(env: Env, collection: Address) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        set_collection_blocked(&env, &collection, false);
 
-        Ok(auction)
+        env.events()
+            .publish(("unblock collection", "collection: "), collection);
+
+        Ok(())
     }
 
     #[allow(dead_code)]
-    pub fn get_active_auctions(
-        env: Env,
-        start_index: Option<u64>,
-        limit: Option<u64>,
-    ) -> Result<Vec<Auction>, ContractError> {
-        let all_auctions = get_auctions(&env, start_index, limit)?;
+    pub fn is_collection_blocked(env: Env, collection: Address) -> bool {
+        is_collection_blocked(&env, &collection)
+    }
 
-        let mut filtered_auctions = vec![&env];
+    /// Prevents `seller` from creating new auctions. Existing auctions are not affected.
+    #[allow(dead_code)]
+    pub fn block_seller(env: Env, seller: Address) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
 
-        for auction in all_auctions.iter() {
-            if auction.status == AuctionStatus::Active {
-                filtered_auctions.push_back(auction);
-            }
-        }
+        set_seller_blocked(&env, &seller, true);
 
-        Ok(filtered_auctions)
+        env.events().publish(("block seller", "seller: "), seller);
+
+        Ok(())
     }
 
     #[allow(dead_code)]
-    pub fn get_auctions_by_seller(
+    pub fn unblock_seller(env: Env, seller: Address) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        set_seller_blocked(&env, &seller, false);
+
+        env.events().publish(("unblock seller", "seller: "), seller);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn is_seller_blocked(env: Env, seller: Address) -> bool {
+        is_seller_blocked(&env, &seller)
+    }
+
+    /// Restricts auctions and listings to verified collections when `required` is set. Besides
+    /// the collections verified by the admin, collections deployed by `deployer` are verified.
+    #[allow(dead_code)]
+    pub fn set_collection_verification(
         env: Env,
-        seller: Address,
-    ) -> Result<Vec<Auction>, ContractError> {
-        let seller_auction_list = get_auctions_by_seller_id(&env, &seller)?;
+        required: bool,
+        deployer: Option<Address>,
+    ) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        let verification = CollectionVerification { required, deployer };
+        save_collection_verification(&env, &verification);
+
+        env.events().publish(
+            ("set collection verification", "verification: "),
+            verification,
+        );
 
-        Ok(seller_auction_list)
+        Ok(())
     }
 
     #[allow(dead_code)]
-    pub fn get_highest_bid(env: Env, auction_id: u64) -> Result<HighestBid, ContractError> {
-        let highest_bid_info = get_highest_bid(&env, auction_id)?;
+    pub fn get_collection_verification(env: Env) -> CollectionVerification {
+        get_collection_verification(&env)
+    }
 
-        Ok(highest_bid_info)
+    #[allow(dead_code)]
+    pub fn verify_collection(env: Env, collection: Address) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        set_collection_verified(&env, &collection, true);
+
+        env.events()
+            .publish(("verify collection", "collection: "), collection);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn unverify_collection(env: Env, collection: Address) -> Result<(), ContractError> {
+        let admin = get_admin_old(&env)?;
+        admin.require_auth();
+
+        set_collection_verified(&env, &collection, false);
+
+        env.events()
+            .publish(("unverify collection", "collection: "), collection);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn is_verified_collection(env: Env, collection: Address) -> bool {
+        is_verified_collection(&env, &collection)
     }
 
     #[allow(dead_code)]
@@ -477,6 +2298,122 @@ impl MarketplaceContract {
 
         Ok(())
     }
+
+    /// Moves auctions created before the persistent storage layout out of instance storage.
+    /// Meant to be called by the admin after `upgrade`, in batches of `limit` auction ids starting
+    /// from `start_index`. Returns the number of migrated auctions.
+    #[allow(dead_code)]
+    pub fn migrate_auctions(
+        env: Env,
+        start_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<u64, ContractError> {
+        let admin: Address = get_admin_old(&env)?;
+        admin.require_auth();
+
+        let start_index = start_index.unwrap_or(DEFAULT_INDEX);
+        let last_index = start_index
+            .saturating_add(limit.unwrap_or(DEFAULT_LIMIT))
+            .saturating_sub(1)
+            .min(get_current_auction_id(&env));
+
+        let mut migrated = 0;
+        for auction_id in start_index..=last_index {
+            if migrate_auction(&env, auction_id) {
+                migrated += 1;
+            }
+        }
+
+        env.events()
+            .publish(("migrate auctions", "migrated: "), migrated);
+
+        Ok(migrated)
+    }
+
+    /// Converts a config saved before royalties, protocol fees and accepted tokens were added.
+    /// The legacy auction token becomes an accepted token with the legacy creation fee, the other
+    /// settings start out as after `initialize`.
+    #[allow(dead_code)]
+    pub fn migrate_config(
+        env: Env,
+        max_royalty_bps: u32,
+        protocol_fee_bps: u32,
+        treasury: Address,
+    ) -> Result<Config, ContractError> {
+        let admin: Address = get_admin_old(&env)?;
+        admin.require_auth();
+
+        let Some(legacy_config) = get_legacy_config(&env) else {
+            log!(
+                &env,
+                "Auction: Migrate config: Config is not in the legacy layout"
+            );
+            return Err(ContractError::NothingToMigrate);
+        };
+
+        // royalty and protocol fee are both taken from the sale price
+        validate_bps(&env, max_royalty_bps.saturating_add(protocol_fee_bps))?;
+
+        let config = save_initial_config(
+            &env,
+            legacy_config.auction_token.clone(),
+            legacy_config.auction_creation_fee,
+            max_royalty_bps,
+            protocol_fee_bps,
+            treasury,
+        );
+
+        env.events().publish(
+            ("migrate config", "auction token: "),
+            legacy_config.auction_token,
+        );
+
+        Ok(config)
+    }
+}
+
+/// Saves the config set up by `initialize` and accepts `auction_token` with the creation fee,
+/// unless it is accepted already
+fn save_initial_config(
+    env: &Env,
+    auction_token: Address,
+    auction_creation_fee: u128,
+    max_royalty_bps: u32,
+    protocol_fee_bps: u32,
+    treasury: Address,
+) -> Config {
+    let mut accepted_tokens = get_accepted_tokens(env);
+    if get_accepted_token(env, &auction_token).is_err() {
+        accepted_tokens.push_back(AcceptedToken {
+            token: auction_token.clone(),
+            creation_fee: auction_creation_fee,
+            min_price: 0,
+        });
+        save_accepted_tokens(env, &accepted_tokens);
+    }
+
+    let config = Config {
+        auction_token,
+        max_royalty_bps,
+        protocol_fee_bps,
+        treasury,
+        // sellers can cancel only auctions without bids, unless the admin allows otherwise
+        allow_cancel_with_bids: false,
+        // anti-sniping extensions are disabled until configured by the admin
+        anti_sniping: AntiSnipingConfig {
+            extension_window: 0,
+            extension_duration: 0,
+            max_total_extension: 0,
+        },
+        // any bid higher than the current highest bid is accepted
+        min_bid_increment: BidIncrement {
+            absolute: 0,
+            bps: 0,
+        },
+    };
+    save_config(env, config.clone());
+
+    config
 }
 
 fn save_auction(env: &Env, auction: &Auction) -> Result<(), ContractError> {
@@ -484,3 +2421,478 @@ fn save_auction(env: &Env, auction: &Auction) -> Result<(), ContractError> {
     save_auction_by_seller(env, &auction.seller, auction)?;
     Ok(())
 }
+
+/// Loads an auction and opens it when it is scheduled and its start time has been reached.
+fn get_started_auction(env: &Env, auction_id: u64) -> Result<Auction, ContractError> {
+    let auction = get_current_auction(env, auction_id)?;
+
+    if auction.status == AuctionStatus::Active
+        && get_auction_by_id(env, auction_id)?.status == AuctionStatus::Scheduled
+    {
+        save_auction(env, &auction)?;
+
+        env.events()
+            .publish(("start auction", "auction id: "), auction_id);
+    }
+
+    Ok(auction)
+}
+
+fn ensure_marketplace_active(env: &Env) -> Result<(), ContractError> {
+    if is_marketplace_paused(env) {
+        log!(env, "Auction: Marketplace is paused");
+        return Err(ContractError::MarketplacePaused);
+    }
+
+    Ok(())
+}
+
+/// Verified collections are either verified by the admin or deployed by the configured deployer
+/// with their upgrades locked, so their admin cannot swap the implementation afterwards
+fn is_verified_collection(env: &Env, collection: &Address) -> bool {
+    is_collection_verified(env, collection)
+        || get_collection_verification(env)
+            .deployer
+            .is_some_and(|deployer| {
+                deployer::Client::new(env, &deployer).is_deployed_collection(collection)
+                    // collections running code from before upgrades could be locked fail this call
+                    && matches!(
+                        collection::Client::new(env, collection).try_are_upgrades_locked(),
+                        Ok(Ok(true))
+                    )
+            })
+}
+
+fn ensure_verified_collection(env: &Env, collection: &Address) -> Result<(), ContractError> {
+    if get_collection_verification(env).required && !is_verified_collection(env, collection) {
+        log!(env, "Auction: Collection is not verified");
+        return Err(ContractError::CollectionNotVerified);
+    }
+
+    Ok(())
+}
+
+/// Blocked sellers and collections cannot be auctioned
+fn ensure_allowed_seller(
+    env: &Env,
+    seller: &Address,
+    collection: &Address,
+) -> Result<(), ContractError> {
+    if is_seller_blocked(env, seller) {
+        log!(env, "Auction: Seller is blocked: ", seller);
+        return Err(ContractError::SellerBlocked);
+    }
+
+    if is_collection_blocked(env, collection) {
+        log!(env, "Auction: Collection is blocked: ", collection);
+        return Err(ContractError::CollectionBlocked);
+    }
+
+    Ok(())
+}
+
+/// Checks that `bidder` may take part in the auction, which private auctions restrict.
+fn ensure_eligible_bidder(
+    env: &Env,
+    auction_id: u64,
+    bidder: &Address,
+) -> Result<(), ContractError> {
+    let eligible = match get_bidder_restriction(env, auction_id) {
+        None => true,
+        Some(BidderRestriction::Allowlist(bidders)) => bidders.contains(bidder),
+        Some(BidderRestriction::TokenGate(gate)) => {
+            collection::Client::new(env, &gate.collection_addr).balance_of(bidder, &gate.item_id)
+                >= gate.min_balance
+        }
+    };
+
+    if !eligible {
+        log!(
+            env,
+            "Auction: Bidder not eligible for auction: ",
+            auction_id
+        );
+        return Err(ContractError::BidderNotEligible);
+    }
+
+    Ok(())
+}
+
+/// Returns the lowest bid the auction accepts next, taking the bid increment into account.
+fn get_min_next_bid(env: &Env, auction: &Auction) -> Result<u64, ContractError> {
+    let highest_bid = match auction.highest_bid {
+        Some(highest_bid) => highest_bid,
+        None => return Ok(1),
+    };
+
+    let bid_increment = get_bid_increment(env, auction.id)?;
+    let relative_increment =
+        (highest_bid as u128 * bid_increment.bps as u128 / MAX_BASIS_POINTS as u128) as u64;
+
+    Ok(highest_bid.saturating_add(bid_increment.absolute.max(relative_increment).max(1)))
+}
+
+/// Binding the commitment to the auction and the bidder prevents copying another bidder's
+/// commitment and revealing the same bid after them
+fn sealed_bid_commitment(
+    env: &Env,
+    auction_id: u64,
+    bidder: &Address,
+    bid_amount: u64,
+    salt: BytesN<32>,
+) -> BytesN<32> {
+    let mut payload = Bytes::from_array(env, &auction_id.to_be_bytes());
+    payload.append(&bidder.clone().to_xdr(env));
+    payload.append(&Bytes::from_array(env, &bid_amount.to_be_bytes()));
+    payload.append(&salt.into());
+
+    env.crypto().sha256(&payload).to_bytes()
+}
+
+/// Settles a sealed-bid auction once the reveal phase is over. The highest revealed bid that
+/// reaches the minimum price wins, ties going to the earlier commitment. With second-price
+/// pricing the winner pays the second highest revealed bid, but at least the minimum price, or
+/// their own bid when neither exists. All remaining escrow, including that of unrevealed bids, is
+/// refunded.
+fn settle_sealed_auction(
+    env: &Env,
+    auction: &mut Auction,
+    sealed_auction: &SealedAuctionInfo,
+) -> Result<(), ContractError> {
+    let bidders = get_sealed_bidders(env, auction.id);
+
+    let mut highest: Option<(Address, u64)> = None;
+    let mut second_highest_bid: Option<u64> = None;
+    for bidder in bidders.iter() {
+        let revealed_bid = match get_sealed_bid(env, auction.id, &bidder)
+            .and_then(|sealed_bid| sealed_bid.revealed_bid)
+        {
+            Some(revealed_bid) => revealed_bid,
+            None => continue,
+        };
+
+        match highest.as_ref().map(|(_, highest_bid)| *highest_bid) {
+            Some(highest_bid) if revealed_bid <= highest_bid => {
+                second_highest_bid = second_highest_bid.max(Some(revealed_bid));
+            }
+            previous_highest_bid => {
+                second_highest_bid = second_highest_bid.max(previous_highest_bid);
+                highest = Some((bidder, revealed_bid));
+            }
+        }
+    }
+
+    let minimum_price = auction.item_info.minimum_price;
+    let winner = highest
+        .filter(|(_, highest_bid)| minimum_price.is_none_or(|min_price| *highest_bid >= min_price))
+        .filter(|_| can_deliver_items(env, auction));
+
+    let mut price = 0;
+    if let Some((winner, highest_bid)) = winner.clone() {
+        price = match sealed_auction.pricing {
+            SealedBidPricing::FirstPrice => highest_bid,
+            SealedBidPricing::SecondPrice => {
+                second_highest_bid.max(minimum_price).unwrap_or(highest_bid)
+            }
+        };
+
+        distribute_funds(
+            env,
+            Sale::auction(env, auction),
+            &env.current_contract_address(),
+            price,
+        )?;
+
+        deliver_items(env, auction, &winner);
+
+        set_highest_bid(env, auction.id, price, winner.clone())?;
+        auction.highest_bid = Some(price);
+
+        env.events()
+            .publish(("finalize auction", "highest bidder: "), winner);
+        env.events()
+            .publish(("finalize auction", "highest bid: "), price);
+    } else {
+        return_items(env, auction)?;
+        env.events().publish(("finalize auction", "no winner"), ());
+    }
+
+    for bidder in bidders.iter() {
+        let sealed_bid = match get_sealed_bid(env, auction.id, &bidder) {
+            Some(sealed_bid) => sealed_bid,
+            None => continue,
+        };
+
+        let refund = match &winner {
+            Some((winner, _)) if *winner == bidder => sealed_bid.escrowed - price,
+            _ => sealed_bid.escrowed,
+        };
+
+        if refund > 0 {
+            credit_refund(env, &bidder, &auction.auction_token, refund);
+
+            env.events()
+                .publish(("finalize auction", "refunded bidder: "), (bidder, refund));
+        }
+    }
+
+    auction.status = AuctionStatus::Ended;
+    save_auction(env, auction)?;
+
+    Ok(())
+}
+
+/// Pushes back the end of the auction when a bid arrives within the configured extension window,
+/// so that other bidders have time to respond.
+fn extend_auction_end_time(env: &Env, auction: &mut Auction) -> Result<(), ContractError> {
+    let anti_sniping = get_config(env)?.anti_sniping;
+    let now = env.ledger().timestamp();
+
+    if anti_sniping.extension_window == 0
+        || auction.end_time.saturating_sub(now) >= anti_sniping.extension_window
+    {
+        return Ok(());
+    }
+
+    let total_extension = get_total_extension(env, auction.id);
+    let extension = (now + anti_sniping.extension_duration)
+        .saturating_sub(auction.end_time)
+        .min(
+            anti_sniping
+                .max_total_extension
+                .saturating_sub(total_extension),
+        );
+
+    if extension == 0 {
+        return Ok(());
+    }
+
+    auction.end_time += extension;
+    set_total_extension(env, auction.id, total_extension + extension);
+
+    env.events().publish(
+        ("auction extended", "auction id: ", "new end time: "),
+        (auction.id, auction.end_time),
+    );
+
+    Ok(())
+}
+
+/// Returns the holder of the auctioned item: the marketplace for escrowed items, the seller for
+/// auctions created before items were escrowed.
+fn item_holder(env: &Env, auction: &Auction) -> Address {
+    if is_item_escrowed(env, auction.id) {
+        env.current_contract_address()
+    } else {
+        auction.seller.clone()
+    }
+}
+
+/// Credits `amount` of `token` to the withdrawable balance of `account`. Refunds and payouts are
+/// never pushed, so an account that cannot receive the token does not block the settlement.
+fn credit_refund(env: &Env, account: &Address, token: &Address, amount: u64) {
+    if amount == 0 {
+        return;
+    }
+
+    let pending_refund = get_pending_refund(env, account, token) + amount as u128;
+    set_pending_refund(env, account, token, pending_refund);
+
+    env.events().publish(
+        ("credit refund", "account: "),
+        (account.clone(), token.clone(), amount),
+    );
+}
+
+/// Returns every item sold by the auction, which is more than one for bundle auctions.
+fn auction_items(env: &Env, auction: &Auction) -> Vec<BundleItem> {
+    get_bundle_items(env, auction.id).unwrap_or_else(|| {
+        vec![
+            env,
+            BundleItem {
+                collection_addr: auction.item_info.collection_addr.clone(),
+                item_id: auction.item_info.item_id,
+                amount: auction.item_info.amount,
+            },
+        ]
+    })
+}
+
+/// Checks that the auctioned items can still be transferred to the winner. Escrowed items always
+/// can, while sellers of non-escrowed items might have moved them or revoked the approval.
+fn can_deliver_items(env: &Env, auction: &Auction) -> bool {
+    let holder = item_holder(env, auction);
+    let marketplace = env.current_contract_address();
+
+    auction_items(env, auction).iter().all(|item| {
+        let nft_client = collection::Client::new(env, &item.collection_addr);
+
+        nft_client.balance_of(&holder, &item.item_id) >= item.amount
+            && (holder == marketplace
+                || nft_client.is_approved_for_all(&holder, &marketplace)
+                || nft_client.is_approved_for_transfer(&holder, &marketplace, &item.item_id))
+    })
+}
+
+/// Transfers all auctioned items to `to`. Any item that cannot be transferred fails the whole
+/// settlement.
+fn deliver_items(env: &Env, auction: &Auction, to: &Address) {
+    let holder = item_holder(env, auction);
+
+    for item in auction_items(env, auction).iter() {
+        collection::Client::new(env, &item.collection_addr).safe_transfer_from(
+            &env.current_contract_address(),
+            &holder,
+            to,
+            &item.item_id,
+            &item.amount,
+        );
+    }
+}
+
+/// Gives the items of an auction that ended without a sale back to the seller.
+fn return_items(env: &Env, auction: &Auction) -> Result<(), ContractError> {
+    if is_item_escrowed(env, auction.id) {
+        deliver_items(env, auction, &auction.seller);
+
+        env.events()
+            .publish(("return item", "auction id: "), auction.id);
+    } else {
+        for item in auction_items(env, auction).iter() {
+            revoke_approval_if_unlisted(env, &auction.seller, &item.collection_addr, item.item_id)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Revokes the marketplace's approval to transfer the seller's item, unless the item is still for
+/// sale in a non-escrowed auction or an active listing.
+fn revoke_approval_if_unlisted(
+    env: &Env,
+    seller: &Address,
+    collection_addr: &Address,
+    item_id: u64,
+) -> Result<(), ContractError> {
+    let still_auctioned = has_unescrowed_auctions(env, seller, collection_addr, item_id);
+    let still_listed = has_active_listings(env, seller, collection_addr, item_id);
+
+    if !still_auctioned && !still_listed {
+        collection::Client::new(env, collection_addr).set_approval_for_transfer(
+            seller,
+            &env.current_contract_address(),
+            &item_id,
+            &false,
+        );
+    }
+
+    Ok(())
+}
+
+/// A sale of items through an auction, a fixed-price listing or an accepted offer
+struct Sale<'a> {
+    kind: &'static str,
+    id: u64,
+    payment_token: &'a Address,
+    items: Vec<BundleItem>,
+    seller: &'a Address,
+}
+
+impl<'a> Sale<'a> {
+    fn auction(env: &Env, auction: &'a Auction) -> Self {
+        Sale {
+            kind: "auction id: ",
+            id: auction.id,
+            payment_token: &auction.auction_token,
+            items: auction_items(env, auction),
+            seller: &auction.seller,
+        }
+    }
+
+    fn listing(env: &Env, listing: &'a Listing) -> Self {
+        Sale {
+            kind: "listing id: ",
+            id: listing.id,
+            payment_token: &listing.payment_token,
+            items: vec![
+                env,
+                BundleItem {
+                    collection_addr: listing.collection_addr.clone(),
+                    item_id: listing.item_id,
+                    amount: listing.amount,
+                },
+            ],
+            seller: &listing.seller,
+        }
+    }
+}
+
+/// Collects `amount` of the payment token from `payer` for the sold items. The protocol fee is
+/// kept by the marketplace, the royalty owed to the creator, as reported by the collection and
+/// capped by `Config::max_royalty_bps`, is credited next and the rest is credited to the seller.
+/// Royalties and proceeds are withdrawn with `withdraw_refund`, so that a receiver that cannot
+/// accept the token does not block the sale. For bundles the royalty of each item is computed on
+/// an equal share of `amount`.
+fn distribute_funds(
+    env: &Env,
+    sale: Sale,
+    payer: &Address,
+    amount: u64,
+) -> Result<(), ContractError> {
+    let config = get_config(env)?;
+
+    if payer != &env.current_contract_address() {
+        token::Client::new(env, sale.payment_token).transfer(
+            payer,
+            &env.current_contract_address(),
+            &(amount as i128),
+        );
+    }
+
+    let protocol_fee =
+        (amount as u128 * config.protocol_fee_bps as u128 / MAX_BASIS_POINTS as u128) as u64;
+
+    if protocol_fee > 0 {
+        accrue_fees(env, sale.payment_token, protocol_fee as u128);
+
+        env.events()
+            .publish(("protocol fee", sale.kind), (sale.id, protocol_fee));
+    }
+
+    let mut seller_amount = amount - protocol_fee;
+
+    let share = amount / sale.items.len() as u64;
+    let max_royalty =
+        (share as u128 * config.max_royalty_bps as u128 / MAX_BASIS_POINTS as u128) as u64;
+
+    for item in sale.items.iter() {
+        let nft_client = collection::Client::new(env, &item.collection_addr);
+
+        // collections without royalties, or that fail to report them, are settled without royalty
+        let royalty = match nft_client.try_royalty_info(&item.item_id, &share) {
+            Ok(Ok((receiver, royalty_amount))) if &receiver != sale.seller => {
+                Some((receiver, royalty_amount.min(max_royalty)))
+            }
+            _ => None,
+        };
+
+        if let Some((receiver, royalty_amount)) = royalty.filter(|(_, amount)| *amount > 0) {
+            credit_refund(env, &receiver, sale.payment_token, royalty_amount);
+            seller_amount -= royalty_amount;
+
+            env.events().publish(
+                ("royalty payout", sale.kind, "receiver: "),
+                (sale.id, receiver, royalty_amount),
+            );
+        }
+    }
+
+    credit_refund(env, sale.seller, sale.payment_token, seller_amount);
+
+    env.events().publish(
+        ("seller payout", sale.kind, "seller: "),
+        (sale.id, sale.seller.clone(), seller_amount),
+    );
+
+    Ok(())
+}
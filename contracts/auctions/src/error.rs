@@ -23,4 +23,26 @@ pub enum ContractError {
     NoBidFound = 16,
     ConfigNotFound = 17,
     AuctionCreationFeeNotCovered = 18,
+    NotEnoughAccruedFees = 19,
+    AuctionCannotBeCancelled = 20,
+    BidIncrementNotMet = 21,
+    InvalidAuctionType = 22,
+    BidAlreadyCommitted = 23,
+    InvalidAuctionPhase = 24,
+    InvalidReveal = 25,
+    ListingNotFound = 26,
+    ListingNotActive = 27,
+    OfferNotFound = 28,
+    OfferNotActive = 29,
+    OfferExpired = 30,
+    TokenNotAccepted = 31,
+    NoRefundPending = 32,
+    AuctionNotStarted = 33,
+    BidderNotEligible = 34,
+    MarketplacePaused = 35,
+    CollectionBlocked = 36,
+    SellerBlocked = 37,
+    CollectionNotVerified = 38,
+    PartialBuyWithBids = 39,
+    NothingToMigrate = 40,
 }
@@ -14,6 +14,12 @@ pub mod collection {
     );
 }
 
+pub mod deployer {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/phoenix_nft_deployer.wasm"
+    );
+}
+
 pub mod token {
     soroban_sdk::contractimport!(
         file = "../../target/wasm32-unknown-unknown/release/soroban_token_contract.wasm"
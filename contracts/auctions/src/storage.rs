@@ -1,5 +1,6 @@
 use soroban_sdk::{
-    contracttype, log, panic_with_error, symbol_short, vec, Address, Env, Symbol, Vec,
+    contracttype, log, panic_with_error, vec, Address, BytesN, Env, IntoVal, Map, Symbol,
+    TryFromVal, Val, Vec,
 };
 
 use crate::error::ContractError;
@@ -13,7 +14,19 @@ pub const LIFETIME_THRESHOLD: u32 = BUMP_AMOUNT - DAY_IN_LEDGERS;
 // since we start counting from 1, default would be 1 as well
 pub const DEFAULT_INDEX: u64 = 1;
 pub const DEFAULT_LIMIT: u64 = 10;
-pub const ADMIN: Symbol = symbol_short!("ADMIN");
+
+// auction indexes are split into buckets, each holding the entries of `ID_BUCKET_SPAN` consecutive
+// auction ids or of `TIME_BUCKET_SPAN` seconds, so that no single entry grows with the index
+pub const ID_BUCKET_SPAN: u64 = 64;
+pub const TIME_BUCKET_SPAN: u64 = 24 * 60 * 60;
+// upper bound on the buckets read for a single page of an index
+pub const MAX_BUCKETS_PER_PAGE: u64 = 16;
+
+// percentages are expressed in basis points, 10_000 being 100%
+pub const MAX_BASIS_POINTS: u32 = 10_000;
+
+// upper bound on the number of items sold in a single bundle auction
+pub const MAX_BUNDLE_SIZE: u32 = 10;
 
 #[contracttype]
 #[derive(Clone)]
@@ -24,6 +37,62 @@ pub enum DataKey {
     AllAuctions,
     HighestBid(u64),
     Config,
+    AccruedFees(Address),
+    TotalExtension(u64),
+    BidIncrement(u64),
+    DutchAuction(u64),
+    SealedAuction(u64),
+    SealedBid(u64, Address),
+    SealedBidders(u64),
+    ListingId,
+    Listing(u64),
+    OfferId,
+    Offer(u64),
+    AcceptedTokens,
+    EscrowedItem(u64),
+    BundleItems(u64),
+    PendingRefund(Address, Address),
+    Auction(u64),
+    StartTime(u64),
+    BidderRestriction(u64),
+    MarketplacePaused,
+    BlockedCollection(Address),
+    BlockedSeller(Address),
+    CollectionVerification,
+    VerifiedCollection(Address),
+    ListLength(ListKey),
+    ListEntry(ListKey, u64),
+    ActiveListings(Address, Address, u64),
+    UnescrowedAuctions(Address, Address, u64),
+    IndexBucket(IndexKey, u64),
+    IndexBounds(IndexKey),
+}
+
+/// Append-only lists stored with one persistent entry per position, so that neither adding to a
+/// list nor reading a page of it loads the whole list
+#[contracttype]
+#[derive(Clone)]
+pub enum ListKey {
+    SellerListings(Address),
+    BuyerOffers(Address),
+    CollectionOffers(Address),
+    AuctionBids(u64),
+    BidderBids(Address),
+}
+
+/// Indexes of auctions, stored as buckets of `(position, auction id)` entries sorted by position.
+/// The position is the auction id itself, except for the ending soon and upcoming indexes that
+/// are sorted by end and start time.
+#[contracttype]
+#[derive(Clone)]
+pub enum IndexKey {
+    SellerAuctions(Address),
+    CollectionAuctions(Address),
+    ItemAuctions(Address, u64),
+    BidderAuctions(Address),
+    StatusAuctions(AuctionStatus),
+    EndingSoon,
+    Upcoming,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -36,6 +105,32 @@ pub struct ItemInfo {
     pub amount: u64,
 }
 
+/// Restricts who can bid on or buy now a private auction
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub enum BidderRestriction {
+    Allowlist(Vec<Address>),
+    TokenGate(TokenGate),
+}
+
+/// Bidders must hold at least `min_balance` of `item_id` in the collection at `collection_addr`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct TokenGate {
+    pub collection_addr: Address,
+    pub item_id: u64,
+    pub min_balance: u64,
+}
+
+/// Struct that describes one of the items sold together in a bundle auction
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct BundleItem {
+    pub collection_addr: Address,
+    pub item_id: u64,
+    pub amount: u64,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 #[contracttype]
 pub struct Auction {
@@ -55,6 +150,35 @@ pub struct HighestBid {
     pub bidder: Address,
 }
 
+/// An accepted bid, kept in the bid history of the auction and of the bidder
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Bid {
+    pub auction_id: u64,
+    pub bidder: Address,
+    pub amount: u64,
+    pub timestamp: u64,
+}
+
+/// A page of auctions returned by the discovery queries, sorted by id. `next_cursor` is passed to
+/// the same query to fetch the auctions that follow and is `None` on the last page. A page holds
+/// fewer than `limit` auctions when it runs into a stretch of the index without any.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct AuctionPage {
+    pub auctions: Vec<Auction>,
+    pub next_cursor: Option<u64>,
+}
+
+/// A page of auctions sorted by time, otherwise the same as `AuctionPage`. `next_cursor` packs
+/// the timestamp of the last auction read in its upper 64 bits and the auction id in the lower.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct TimedAuctionPage {
+    pub auctions: Vec<Auction>,
+    pub next_cursor: Option<u128>,
+}
+
 #[derive(Clone, PartialEq, Debug)]
 #[contracttype]
 pub enum AuctionStatus {
@@ -62,15 +186,246 @@ pub enum AuctionStatus {
     Ended,
     Cancelled,
     Paused,
+    Scheduled,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[contracttype]
 pub struct Config {
+    /// Token used by auctions that do not name a payment token
+    pub auction_token: Address,
+    pub max_royalty_bps: u32,
+    pub protocol_fee_bps: u32,
+    pub treasury: Address,
+    pub allow_cancel_with_bids: bool,
+    pub anti_sniping: AntiSnipingConfig,
+    pub min_bid_increment: BidIncrement,
+}
+
+/// Config of the marketplace before royalties, protocol fees and accepted tokens were added, only
+/// read to migrate it
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct LegacyConfig {
     pub auction_token: Address,
     pub auction_creation_fee: u128,
 }
 
+/// Struct that describes which collections can be listed on the marketplace
+///
+/// * `required` - Whether only verified collections can be auctioned and listed
+/// * `deployer` - `CollectionsDeployer` whose deployed collections count as verified, in addition
+///   to the collections verified by the admin
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct CollectionVerification {
+    pub required: bool,
+    pub deployer: Option<Address>,
+}
+
+/// Struct that describes a token accepted as payment for auctions
+///
+/// * `creation_fee` - Fee charged in this token for creating an auction
+/// * `min_price` - Lowest reserve and buy now price of auctions priced in this token
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct AcceptedToken {
+    pub token: Address,
+    pub creation_fee: u128,
+    pub min_price: u64,
+}
+
+/// Struct that describes by how much a new bid must beat the current highest bid. When both
+/// values are set, the larger resulting increment applies
+///
+/// * `absolute` - Minimum increment in units of the auction token
+/// * `bps` - Minimum increment in basis points of the current highest bid
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct BidIncrement {
+    pub absolute: u64,
+    pub bps: u32,
+}
+
+/// Struct that describes how auctions are extended when bids arrive at the last moment
+///
+/// * `extension_window` - Bids placed within this many seconds before the end extend the auction.
+/// `0` disables the extension
+/// * `extension_duration` - After an extension, the auction ends this many seconds after the bid
+/// * `max_total_extension` - Upper bound, in seconds, on how much a single auction can be extended
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct AntiSnipingConfig {
+    pub extension_window: u64,
+    pub extension_duration: u64,
+    pub max_total_extension: u64,
+}
+
+/// Describes how the price of a Dutch auction decreases over its duration
+///
+/// * `Linear` - The price decreases continuously
+/// * `Stepwise` - The price decreases every given number of seconds
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub enum PriceDecay {
+    Linear,
+    Stepwise(u64),
+}
+
+/// Struct that describes how the price of a Dutch auction moves from `start_price` down to
+/// `end_price` over the auction duration
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct DutchPriceSchedule {
+    pub start_price: u64,
+    pub end_price: u64,
+    pub decay: PriceDecay,
+}
+
+/// Struct that describes the price schedule of a Dutch auction, which moves from `start_price`
+/// at `start_time` down to `end_price` after `duration` seconds
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct DutchAuctionInfo {
+    pub start_price: u64,
+    pub end_price: u64,
+    pub start_time: u64,
+    pub duration: u64,
+    pub decay: PriceDecay,
+}
+
+impl DutchAuctionInfo {
+    pub fn price_at(&self, timestamp: u64) -> u64 {
+        let elapsed = timestamp.saturating_sub(self.start_time);
+        if elapsed >= self.duration {
+            return self.end_price;
+        }
+
+        let elapsed = match self.decay {
+            PriceDecay::Linear => elapsed,
+            PriceDecay::Stepwise(step) => elapsed - elapsed % step,
+        };
+
+        let decrease =
+            (self.start_price - self.end_price) as u128 * elapsed as u128 / self.duration as u128;
+
+        self.start_price - decrease as u64
+    }
+}
+
+/// Describes what the winner of a sealed-bid auction pays
+///
+/// * `FirstPrice` - The winner pays their own bid
+/// * `SecondPrice` - The winner pays the second highest revealed bid (Vickrey auction)
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub enum SealedBidPricing {
+    FirstPrice,
+    SecondPrice,
+}
+
+/// Struct that describes the parameters of a new sealed-bid auction
+///
+/// * `commit_duration` - Seconds during which bids can be committed
+/// * `reveal_duration` - Seconds during which committed bids can be revealed, after the commit
+/// phase
+/// * `deposit` - Amount of the auction token escrowed with every commitment
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SealedAuctionParams {
+    pub commit_duration: u64,
+    pub reveal_duration: u64,
+    pub deposit: u64,
+    pub pricing: SealedBidPricing,
+}
+
+/// Struct that describes the phases of a sealed-bid auction. Bids are committed until
+/// `commit_end` and revealed until the end of the auction
+///
+/// * `deposit` - Amount of the auction token escrowed with every commitment
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SealedAuctionInfo {
+    pub commit_end: u64,
+    pub deposit: u64,
+    pub pricing: SealedBidPricing,
+}
+
+/// Struct that describes the commitment of a single bidder in a sealed-bid auction
+///
+/// * `commitment` - `sha256` of the big-endian auction id, the XDR encoded bidder address, the
+///   big-endian bid amount and a 32 bytes salt
+/// * `escrowed` - Amount of the auction token currently held by the marketplace for this bid
+/// * `revealed_bid` - The bid amount, once revealed
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct SealedBid {
+    pub commitment: BytesN<32>,
+    pub escrowed: u64,
+    pub revealed_bid: Option<u64>,
+}
+
+/// Struct that describes an item sold for a fixed price, independently of any auction
+///
+/// * `amount` - Number of items still available
+/// * `price` - Price of a single item, in units of `payment_token`
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Listing {
+    pub id: u64,
+    pub collection_addr: Address,
+    pub item_id: u64,
+    pub seller: Address,
+    pub amount: u64,
+    pub price: u64,
+    pub payment_token: Address,
+    pub status: ListingStatus,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[contracttype]
+pub enum ListingStatus {
+    Active,
+    Sold,
+    Delisted,
+}
+
+/// Struct that describes an offer to buy items that are not necessarily for sale. The payment is
+/// escrowed by the marketplace until the offer is accepted or cancelled
+///
+/// * `price` - Total price offered for the requested items, in units of `payment_token`
+/// * `expiration` - Timestamp after which the offer can no longer be accepted
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Offer {
+    pub id: u64,
+    pub buyer: Address,
+    pub item: OfferItem,
+    pub price: u64,
+    pub payment_token: Address,
+    pub expiration: u64,
+    pub status: OfferStatus,
+}
+
+/// Struct that describes the items requested by an offer
+///
+/// * `item_id` - The requested item, `None` when any item of the collection is accepted
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OfferItem {
+    pub collection_addr: Address,
+    pub item_id: Option<u64>,
+    pub amount: u64,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[contracttype]
+pub enum OfferStatus {
+    Active,
+    Accepted,
+    Cancelled,
+}
+
 pub fn generate_auction_id(env: &Env) -> Result<u64, ContractError> {
     let id = env
         .storage()
@@ -86,32 +441,30 @@ pub fn generate_auction_id(env: &Env) -> Result<u64, ContractError> {
     Ok(id)
 }
 
+pub fn get_current_auction_id(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::AuctionId)
+        .unwrap_or_default()
+}
+
 pub fn get_auctions(
     env: &Env,
     start_index: Option<u64>,
     limit: Option<u64>,
 ) -> Result<Vec<Auction>, ContractError> {
     let start_index = start_index.unwrap_or(DEFAULT_INDEX);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
 
-    // this is a safeguard only for the case when `DEFAULT_LIMIT` is higher than the actually
-    // saved auctions and we use `None` and `None` for `start_index` and `limit`.
-    // I.e. we have just 3 auctions and we want to query them
-    let current_highest_id: u64 = env
-        .storage()
-        .instance()
-        .get(&DataKey::AuctionId)
-        .expect("no previous value");
-
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(current_highest_id);
+    let last_index = start_index
+        .saturating_add(limit)
+        .saturating_sub(1)
+        .min(get_current_auction_id(env));
 
-    let mut auctions = vec![&env];
+    let mut auctions = vec![env];
 
-    for id in start_index..=limit {
-        match get_auction_by_id(env, id) {
-            Ok(auction) => auctions.push_back(auction),
-            Err(ContractError::AuctionNotFound) => continue,
-            Err(e) => return Err(e),
-        }
+    for id in start_index..=last_index {
+        auctions.push_back(get_current_auction(env, id)?);
     }
 
     Ok(auctions)
@@ -122,163 +475,1106 @@ pub fn save_auction_by_id(
     auction_id: u64,
     auction: &Auction,
 ) -> Result<(), ContractError> {
-    env.storage().instance().set(&auction_id, auction);
-    env.storage()
-        .instance()
-        .extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT);
-
-    Ok(())
-}
-
-pub fn save_auction_by_seller(
-    env: &Env,
-    seller: &Address,
-    auction: &Auction,
-) -> Result<(), ContractError> {
-    let mut seller_auctions_list: Vec<Auction> =
-        env.storage().instance().get(seller).unwrap_or(vec![&env]);
+    let key = DataKey::Auction(auction_id);
+    let previous: Option<Auction> = get_persistent(env, &key);
 
-    match seller_auctions_list.iter().position(|a| a.id == auction.id) {
-        Some(existing_idx) => seller_auctions_list.set(existing_idx as u32, auction.clone()),
-        None => seller_auctions_list.push_back(auction.clone()),
+    // only migrated auctions leave the item with the seller, they are counted while they are live
+    let is_live = |auction: &Auction| {
+        auction.status == AuctionStatus::Active || auction.status == AuctionStatus::Paused
     };
+    if previous.as_ref().is_some_and(is_live)
+        && !is_live(auction)
+        && !is_item_escrowed(env, auction_id)
+    {
+        update_unescrowed_auctions(env, auction, false);
+    }
 
-    env.storage().instance().set(seller, &seller_auctions_list);
-
-    env.storage()
-        .instance()
-        .extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT);
+    set_persistent(env, &key, auction);
+    update_auction_indexes(env, previous.as_ref(), auction);
 
     Ok(())
 }
 
-pub fn get_auction_by_id(env: &Env, auction_id: u64) -> Result<Auction, ContractError> {
-    let auction = env
-        .storage()
-        .instance()
-        .get(&auction_id)
-        .unwrap_or_else(|| {
-            log!(env, "Auction: Get auction by id: Auction not present");
-            panic_with_error!(&env, ContractError::AuctionNotFound);
-        });
-    env.storage()
-        .instance()
-        .extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT);
+/// Keeps the collection, item, status and ending soon indexes in sync with the saved auction
+fn update_auction_indexes(env: &Env, previous: Option<&Auction>, auction: &Auction) {
+    if previous.is_none() {
+        let item_info = &auction.item_info;
+        add_to_id_index(
+            env,
+            &IndexKey::CollectionAuctions(item_info.collection_addr.clone()),
+            auction.id,
+        );
+        add_to_id_index(
+            env,
+            &IndexKey::ItemAuctions(item_info.collection_addr.clone(), item_info.item_id),
+            auction.id,
+        );
+    }
+
+    let previous_status = previous.map(|previous| previous.status.clone());
+    if previous_status.as_ref() != Some(&auction.status) {
+        if let Some(status) = previous_status {
+            remove_from_id_index(env, &IndexKey::StatusAuctions(status), auction.id);
+        }
+        add_to_id_index(
+            env,
+            &IndexKey::StatusAuctions(auction.status.clone()),
+            auction.id,
+        );
+    }
 
-    auction
+    // active auctions are ending, sorted by their end time. Scheduled auctions are indexed too, so
+    // that they show up as soon as they start
+    let is_ending = |auction: &Auction| {
+        auction.status == AuctionStatus::Active || auction.status == AuctionStatus::Scheduled
+    };
+    let previous_end_time = previous
+        .filter(|previous| is_ending(previous))
+        .map(|previous| previous.end_time);
+    let end_time = is_ending(auction).then_some(auction.end_time);
+    update_time_index(
+        env,
+        &IndexKey::EndingSoon,
+        auction.id,
+        previous_end_time,
+        end_time,
+    );
+
+    // scheduled auctions are upcoming, sorted by their start time
+    let was_scheduled =
+        previous.is_some_and(|previous| previous.status == AuctionStatus::Scheduled);
+    let is_scheduled = auction.status == AuctionStatus::Scheduled;
+    if was_scheduled != is_scheduled {
+        let start_time = get_start_time(env, auction.id);
+        update_time_index(
+            env,
+            &IndexKey::Upcoming,
+            auction.id,
+            start_time.filter(|_| was_scheduled),
+            start_time.filter(|_| is_scheduled),
+        );
+    }
 }
 
-pub fn get_auctions_by_seller_id(
+/// Moves `auction_id` within an index sorted by time
+fn update_time_index(
     env: &Env,
-    seller: &Address,
-) -> Result<Vec<Auction>, ContractError> {
-    let seller_auctions_list = env.storage().instance().get(seller).unwrap_or_else(|| {
-        log!(env, "Auction: Get auction by seller: No auctions found");
-        panic_with_error!(&env, ContractError::AuctionNotFound);
-    });
-    env.storage()
-        .instance()
-        .extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT);
+    index: &IndexKey,
+    auction_id: u64,
+    previous_time: Option<u64>,
+    time: Option<u64>,
+) {
+    if previous_time == time {
+        return;
+    }
 
-    Ok(seller_auctions_list)
+    if let Some(previous_time) = previous_time {
+        remove_from_index(env, index, previous_time, auction_id);
+    }
+    if let Some(time) = time {
+        add_to_index(env, index, time, auction_id);
+    }
 }
 
-pub fn validate_input_params(env: &Env, values_to_check: &[&u64]) -> Result<(), ContractError> {
-    values_to_check.iter().for_each(|i| {
-        if i < &&1 {
-            log!(
-                &env,
-                "Auction: Validate input: parameter is less than 1: ",
-                **i
-            );
-            panic_with_error!(&env, ContractError::InvalidInputs);
-        }
-    });
+/// `(position, auction id)` entry of an index
+type IndexEntry = (u64, u64);
 
-    Ok(())
-}
-pub fn is_initialized(env: &Env) -> bool {
-    env.storage()
-        .persistent()
-        .get(&DataKey::IsInitialized)
-        .unwrap_or(false)
+fn index_bucket_span(index: &IndexKey) -> u64 {
+    match index {
+        IndexKey::EndingSoon | IndexKey::Upcoming => TIME_BUCKET_SPAN,
+        _ => ID_BUCKET_SPAN,
+    }
 }
 
-pub fn set_initialized(env: &Env) {
-    env.storage()
-        .persistent()
-        .set(&DataKey::IsInitialized, &true);
+fn get_index_bucket(env: &Env, index: &IndexKey, bucket: u64) -> Vec<IndexEntry> {
+    get_persistent(env, &DataKey::IndexBucket(index.clone(), bucket)).unwrap_or(vec![env])
 }
 
-pub fn save_admin_old(env: &Env, admin: &Address) {
-    env.storage().persistent().set(&DataKey::Admin, &admin);
-    env.storage()
-        .persistent()
-        .extend_ttl(&DataKey::Admin, LIFETIME_THRESHOLD, BUMP_AMOUNT);
-}
+fn add_to_index(env: &Env, index: &IndexKey, position: u64, auction_id: u64) {
+    let bucket = position / index_bucket_span(index);
+    let mut entries = get_index_bucket(env, index, bucket);
 
-pub fn get_admin_old(env: &Env) -> Result<Address, ContractError> {
-    let admin = env
-        .storage()
-        .persistent()
-        .get(&DataKey::Admin)
-        .unwrap_or_else(|| {
-            log!(env, "Auction: Get Admin: Admin not found");
-            Err(ContractError::AdminNotFound)
-        })?;
-    env.storage().persistent().has(&DataKey::Admin).then(|| {
-        env.storage()
-            .persistent()
-            .extend_ttl(&DataKey::Admin, LIFETIME_THRESHOLD, BUMP_AMOUNT);
-    });
+    if let Err(insert_at) = entries.binary_search((position, auction_id)) {
+        entries.insert(insert_at, (position, auction_id));
+        set_persistent(env, &DataKey::IndexBucket(index.clone(), bucket), &entries);
 
-    Ok(admin)
+        // the bounds only ever widen, they keep the pages from scanning buckets never used
+        let bounds_key = DataKey::IndexBounds(index.clone());
+        let bounds = match get_persistent::<(u64, u64)>(env, &bounds_key) {
+            Some((first_bucket, last_bucket)) => {
+                (first_bucket.min(bucket), last_bucket.max(bucket))
+            }
+            None => (bucket, bucket),
+        };
+        set_persistent(env, &bounds_key, &bounds);
+    }
 }
 
-pub fn update_admin(env: &Env, new_admin: &Address) -> Result<Address, ContractError> {
-    env.storage().persistent().set(&DataKey::Admin, new_admin);
+fn remove_from_index(env: &Env, index: &IndexKey, position: u64, auction_id: u64) {
+    let bucket = position / index_bucket_span(index);
+    let mut entries = get_index_bucket(env, index, bucket);
 
-    Ok(new_admin.clone())
-}
+    if let Ok(entry_index) = entries.binary_search((position, auction_id)) {
+        entries.remove(entry_index);
 
-pub fn get_highest_bid(env: &Env, auction_id: u64) -> Result<HighestBid, ContractError> {
-    let highest_bid = env
-        .storage()
-        .instance()
-        .get(&DataKey::HighestBid(auction_id))
-        .unwrap_or(HighestBid {
-            bid: 0,
-            // I know
-            bidder: get_admin_old(env)?,
-        });
+        let key = DataKey::IndexBucket(index.clone(), bucket);
+        if entries.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            set_persistent(env, &key, &entries);
+        }
+    }
+}
 
-    env.storage()
-        .instance()
-        .has(&DataKey::HighestBid(auction_id))
-        .then(|| {
-            env.storage()
-                .instance()
-                .extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT)
-        });
+fn add_to_id_index(env: &Env, index: &IndexKey, auction_id: u64) {
+    add_to_index(env, index, auction_id, auction_id);
+}
 
-    Ok(highest_bid)
+fn remove_from_id_index(env: &Env, index: &IndexKey, auction_id: u64) {
+    remove_from_index(env, index, auction_id, auction_id);
 }
 
-pub fn set_highest_bid(
+/// Reads the auctions of `indexes`, which share their bucket span, that come after the entry
+/// `after` and are positioned at `from` or later, keeping those that match `include`. At most
+/// `limit` entries are read from at most `MAX_BUCKETS_PER_PAGE` buckets, the last entry read is
+/// returned along with the auctions unless the end of the indexes was reached.
+fn get_index_page(
     env: &Env,
-    auction_id: u64,
-    bid: u64,
-    bidder: Address,
+    indexes: &[IndexKey],
+    from: u64,
+    after: Option<IndexEntry>,
+    limit: Option<u64>,
+    include: impl Fn(&Auction) -> bool,
+) -> Result<(Vec<Auction>, Option<IndexEntry>), ContractError> {
+    let mut auctions = vec![env];
+
+    let mut bounds: Option<(u64, u64)> = None;
+    for index in indexes {
+        if let Some((first_bucket, last_bucket)) =
+            get_persistent::<(u64, u64)>(env, &DataKey::IndexBounds(index.clone()))
+        {
+            bounds = Some(match bounds {
+                Some((first, last)) => (first.min(first_bucket), last.max(last_bucket)),
+                None => (first_bucket, last_bucket),
+            });
+        }
+    }
+    let Some((first_bucket, last_bucket)) = bounds else {
+        return Ok((auctions, None));
+    };
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let span = index_bucket_span(&indexes[0]);
+    let from = after.map_or(from, |(position, _)| position.max(from));
+
+    let mut bucket = (from / span).max(first_bucket);
+    let last_page_bucket = last_bucket.min(bucket.saturating_add(MAX_BUCKETS_PER_PAGE - 1));
+    let mut last_read = after;
+    let mut read = 0;
+
+    while bucket <= last_page_bucket {
+        let mut entries = get_index_bucket(env, &indexes[0], bucket);
+        for index in &indexes[1..] {
+            for entry in get_index_bucket(env, index, bucket).iter() {
+                let insert_at = entries.binary_search(entry).unwrap_or_else(|at| at);
+                entries.insert(insert_at, entry);
+            }
+        }
+
+        for entry in entries.iter() {
+            if entry.0 < from || after.is_some_and(|after| entry <= after) {
+                continue;
+            }
+            if read == limit {
+                return Ok((auctions, last_read));
+            }
+
+            let auction = get_current_auction(env, entry.1)?;
+            if include(&auction) {
+                auctions.push_back(auction);
+            }
+            last_read = Some(entry);
+            read += 1;
+        }
+        bucket += 1;
+    }
+
+    // the page ends before the indexes do, the next one starts with the next bucket
+    let next = (bucket <= last_bucket).then(|| (bucket * span - 1, u64::MAX));
+
+    Ok((auctions, next))
+}
+
+/// Returns a page of indexes sorted by auction id, starting after the auction id `cursor`
+fn get_id_index_page(
+    env: &Env,
+    indexes: &[IndexKey],
+    cursor: Option<u64>,
+    limit: Option<u64>,
+    include: impl Fn(&Auction) -> bool,
+) -> Result<AuctionPage, ContractError> {
+    let after = cursor.map(|auction_id| (auction_id, auction_id));
+    let (auctions, next) = get_index_page(env, indexes, 0, after, limit, include)?;
+
+    Ok(AuctionPage {
+        auctions,
+        next_cursor: next.map(|(auction_id, _)| auction_id),
+    })
+}
+
+/// Returns a page of an index sorted by time, starting after `cursor` or, without one, with the
+/// first auction whose timestamp is not before `from`
+fn get_time_index_page(
+    env: &Env,
+    index: &IndexKey,
+    from: u64,
+    cursor: Option<u128>,
+    limit: Option<u64>,
+    include: impl Fn(&Auction) -> bool,
+) -> Result<TimedAuctionPage, ContractError> {
+    let after = cursor.map(|cursor| ((cursor >> 64) as u64, cursor as u64));
+    let (auctions, next) = get_index_page(
+        env,
+        core::slice::from_ref(index),
+        from,
+        after,
+        limit,
+        include,
+    )?;
+
+    Ok(TimedAuctionPage {
+        auctions,
+        next_cursor: next.map(|(time, auction_id)| (time as u128) << 64 | auction_id as u128),
+    })
+}
+
+pub fn get_auctions_by_seller(
+    env: &Env,
+    seller: &Address,
+    cursor: Option<u64>,
+    limit: Option<u64>,
+) -> Result<AuctionPage, ContractError> {
+    get_id_index_page(
+        env,
+        &[IndexKey::SellerAuctions(seller.clone())],
+        cursor,
+        limit,
+        |_| true,
+    )
+}
+
+pub fn get_auctions_by_collection(
+    env: &Env,
+    collection_addr: &Address,
+    cursor: Option<u64>,
+    limit: Option<u64>,
+) -> Result<AuctionPage, ContractError> {
+    get_id_index_page(
+        env,
+        &[IndexKey::CollectionAuctions(collection_addr.clone())],
+        cursor,
+        limit,
+        |_| true,
+    )
+}
+
+pub fn get_auctions_by_item(
+    env: &Env,
+    collection_addr: &Address,
+    item_id: u64,
+    cursor: Option<u64>,
+    limit: Option<u64>,
+) -> Result<AuctionPage, ContractError> {
+    get_id_index_page(
+        env,
+        &[IndexKey::ItemAuctions(collection_addr.clone(), item_id)],
+        cursor,
+        limit,
+        |_| true,
+    )
+}
+
+pub fn get_auctions_by_highest_bidder(
+    env: &Env,
+    bidder: &Address,
+    cursor: Option<u64>,
+    limit: Option<u64>,
+) -> Result<AuctionPage, ContractError> {
+    get_id_index_page(
+        env,
+        &[IndexKey::BidderAuctions(bidder.clone())],
+        cursor,
+        limit,
+        |_| true,
+    )
+}
+
+pub fn get_auctions_by_status(
+    env: &Env,
+    status: AuctionStatus,
+    cursor: Option<u64>,
+    limit: Option<u64>,
+) -> Result<AuctionPage, ContractError> {
+    // scheduled auctions count as active from their start time on, before their stored status
+    // catches up
+    let status_index = [IndexKey::StatusAuctions(status.clone())];
+    let active_indexes = [
+        IndexKey::StatusAuctions(AuctionStatus::Active),
+        IndexKey::StatusAuctions(AuctionStatus::Scheduled),
+    ];
+    let indexes: &[IndexKey] = if status == AuctionStatus::Active {
+        &active_indexes
+    } else {
+        &status_index
+    };
+
+    get_id_index_page(env, indexes, cursor, limit, |auction| {
+        auction.status == status
+    })
+}
+
+/// Active auctions sorted by end time. Without a cursor the page starts with the first auction
+/// that has not ended yet.
+pub fn get_ending_soon_auctions(
+    env: &Env,
+    cursor: Option<u128>,
+    limit: Option<u64>,
+) -> Result<TimedAuctionPage, ContractError> {
+    get_time_index_page(
+        env,
+        &IndexKey::EndingSoon,
+        env.ledger().timestamp(),
+        cursor,
+        limit,
+        |auction| auction.status == AuctionStatus::Active,
+    )
+}
+
+/// Scheduled auctions sorted by start time. Without a cursor the page starts with the first
+/// auction that has not started yet.
+pub fn get_upcoming_auctions(
+    env: &Env,
+    cursor: Option<u128>,
+    limit: Option<u64>,
+) -> Result<TimedAuctionPage, ContractError> {
+    get_time_index_page(
+        env,
+        &IndexKey::Upcoming,
+        env.ledger().timestamp() + 1,
+        cursor,
+        limit,
+        |_| true,
+    )
+}
+
+pub fn save_auction_by_seller(
+    env: &Env,
+    seller: &Address,
+    auction: &Auction,
+) -> Result<(), ContractError> {
+    add_to_id_index(env, &IndexKey::SellerAuctions(seller.clone()), auction.id);
+
+    Ok(())
+}
+
+pub fn get_auction_by_id(env: &Env, auction_id: u64) -> Result<Auction, ContractError> {
+    let auction = get_persistent(env, &DataKey::Auction(auction_id)).unwrap_or_else(|| {
+        log!(env, "Auction: Get auction by id: Auction not present");
+        panic_with_error!(&env, ContractError::AuctionNotFound);
+    });
+
+    Ok(auction)
+}
+
+/// Reads an auction as it currently stands. A scheduled auction whose start time has been reached
+/// is reported as active, its stored status follows the next time the auction is updated.
+pub fn get_current_auction(env: &Env, auction_id: u64) -> Result<Auction, ContractError> {
+    let mut auction = get_auction_by_id(env, auction_id)?;
+
+    if auction.status == AuctionStatus::Scheduled
+        && get_start_time(env, auction_id)
+            .is_some_and(|start_time| env.ledger().timestamp() >= start_time)
+    {
+        auction.status = AuctionStatus::Active;
+    }
+
+    Ok(auction)
+}
+
+pub fn validate_input_params(env: &Env, values_to_check: &[&u64]) -> Result<(), ContractError> {
+    values_to_check.iter().for_each(|i| {
+        if i < &&1 {
+            log!(
+                &env,
+                "Auction: Validate input: parameter is less than 1: ",
+                **i
+            );
+            panic_with_error!(&env, ContractError::InvalidInputs);
+        }
+    });
+
+    Ok(())
+}
+pub fn validate_bps(env: &Env, bps: u32) -> Result<(), ContractError> {
+    if bps > MAX_BASIS_POINTS {
+        log!(
+            &env,
+            "Auction: Validate basis points: value over the maximum: ",
+            bps
+        );
+        return Err(ContractError::InvalidInputs);
+    }
+
+    Ok(())
+}
+
+pub fn is_initialized(env: &Env) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::IsInitialized)
+        .unwrap_or(false)
+}
+
+pub fn set_initialized(env: &Env) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::IsInitialized, &true);
+}
+
+pub fn save_admin_old(env: &Env, admin: &Address) {
+    env.storage().persistent().set(&DataKey::Admin, &admin);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::Admin, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+}
+
+pub fn get_admin_old(env: &Env) -> Result<Address, ContractError> {
+    let admin = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Admin)
+        .unwrap_or_else(|| {
+            log!(env, "Auction: Get Admin: Admin not found");
+            Err(ContractError::AdminNotFound)
+        })?;
+    env.storage().persistent().has(&DataKey::Admin).then(|| {
+        env.storage()
+            .persistent()
+            .extend_ttl(&DataKey::Admin, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+    });
+
+    Ok(admin)
+}
+
+pub fn update_admin(env: &Env, new_admin: &Address) -> Result<Address, ContractError> {
+    env.storage().persistent().set(&DataKey::Admin, new_admin);
+
+    Ok(new_admin.clone())
+}
+
+pub fn get_highest_bid(env: &Env, auction_id: u64) -> Result<HighestBid, ContractError> {
+    let highest_bid = match get_persistent(env, &DataKey::HighestBid(auction_id)) {
+        Some(highest_bid) => highest_bid,
+        None => HighestBid {
+            bid: 0,
+            // I know
+            bidder: get_admin_old(env)?,
+        },
+    };
+
+    Ok(highest_bid)
+}
+
+pub fn set_highest_bid(
+    env: &Env,
+    auction_id: u64,
+    bid: u64,
+    bidder: Address,
 ) -> Result<(), ContractError> {
-    env.storage().instance().set(
-        &DataKey::HighestBid(auction_id),
-        &HighestBid { bid, bidder },
+    let key = DataKey::HighestBid(auction_id);
+
+    if let Some(previous) = get_persistent::<HighestBid>(env, &key) {
+        remove_from_id_index(env, &IndexKey::BidderAuctions(previous.bidder), auction_id);
+    }
+    add_to_id_index(env, &IndexKey::BidderAuctions(bidder.clone()), auction_id);
+
+    set_persistent(env, &key, &HighestBid { bid, bidder });
+
+    Ok(())
+}
+
+/// Bids are stored once in the history of the auction, the history of the bidder refers to them
+/// by their auction id and position
+pub fn record_bid(env: &Env, bid: &Bid) {
+    let auction_bids = ListKey::AuctionBids(bid.auction_id);
+    let position = get_list_length(env, &auction_bids);
+
+    push_to_list(env, &auction_bids, bid);
+    push_to_list(
+        env,
+        &ListKey::BidderBids(bid.bidder.clone()),
+        &(bid.auction_id, position),
     );
+}
+
+/// Returns up to `limit` bids of the auction, starting with the bid at `start_index`, 0 being the
+/// oldest one.
+pub fn get_bids(
+    env: &Env,
+    auction_id: u64,
+    start_index: Option<u64>,
+    limit: Option<u64>,
+) -> Vec<Bid> {
+    get_list_page(env, &ListKey::AuctionBids(auction_id), start_index, limit)
+}
+
+/// Returns up to `limit` bids of the bidder across all auctions, starting with the bid at
+/// `start_index`, 0 being the oldest one.
+pub fn get_bids_by_bidder(
+    env: &Env,
+    bidder: &Address,
+    start_index: Option<u64>,
+    limit: Option<u64>,
+) -> Vec<Bid> {
+    let mut bids = vec![env];
+    for (auction_id, position) in get_list_page::<(u64, u64)>(
+        env,
+        &ListKey::BidderBids(bidder.clone()),
+        start_index,
+        limit,
+    )
+    .iter()
+    {
+        if let Some(bid) = get_persistent(
+            env,
+            &DataKey::ListEntry(ListKey::AuctionBids(auction_id), position),
+        ) {
+            bids.push_back(bid);
+        }
+    }
+
+    bids
+}
+
+pub fn get_pending_refund(env: &Env, account: &Address, token: &Address) -> u128 {
+    get_persistent(env, &DataKey::PendingRefund(account.clone(), token.clone())).unwrap_or_default()
+}
+
+pub fn set_pending_refund(env: &Env, account: &Address, token: &Address, amount: u128) {
+    let key = DataKey::PendingRefund(account.clone(), token.clone());
+
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        set_persistent(env, &key, &amount);
+    }
+}
+
+/// Only private auctions restrict their bidders
+pub fn get_bidder_restriction(env: &Env, auction_id: u64) -> Option<BidderRestriction> {
+    get_persistent(env, &DataKey::BidderRestriction(auction_id))
+}
+
+pub fn save_bidder_restriction(env: &Env, auction_id: u64, restriction: &BidderRestriction) {
+    set_persistent(env, &DataKey::BidderRestriction(auction_id), restriction);
+}
+
+pub fn is_marketplace_paused(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::MarketplacePaused)
+        .unwrap_or(false)
+}
+
+pub fn set_marketplace_paused(env: &Env, paused: bool) {
+    env.storage()
+        .instance()
+        .set(&DataKey::MarketplacePaused, &paused);
     env.storage()
         .instance()
         .extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT);
+}
 
-    Ok(())
+pub fn is_collection_blocked(env: &Env, collection: &Address) -> bool {
+    get_persistent(env, &DataKey::BlockedCollection(collection.clone())).unwrap_or(false)
+}
+
+pub fn set_collection_blocked(env: &Env, collection: &Address, blocked: bool) {
+    set_flag(
+        env,
+        &DataKey::BlockedCollection(collection.clone()),
+        blocked,
+    );
+}
+
+pub fn is_seller_blocked(env: &Env, seller: &Address) -> bool {
+    get_persistent(env, &DataKey::BlockedSeller(seller.clone())).unwrap_or(false)
+}
+
+pub fn set_seller_blocked(env: &Env, seller: &Address, blocked: bool) {
+    set_flag(env, &DataKey::BlockedSeller(seller.clone()), blocked);
+}
+
+pub fn get_collection_verification(env: &Env) -> CollectionVerification {
+    get_persistent(env, &DataKey::CollectionVerification).unwrap_or(CollectionVerification {
+        required: false,
+        deployer: None,
+    })
+}
+
+pub fn save_collection_verification(env: &Env, verification: &CollectionVerification) {
+    set_persistent(env, &DataKey::CollectionVerification, verification);
+}
+
+pub fn is_collection_verified(env: &Env, collection: &Address) -> bool {
+    get_persistent(env, &DataKey::VerifiedCollection(collection.clone())).unwrap_or(false)
+}
+
+pub fn set_collection_verified(env: &Env, collection: &Address, verified: bool) {
+    set_flag(
+        env,
+        &DataKey::VerifiedCollection(collection.clone()),
+        verified,
+    );
+}
+
+fn set_flag(env: &Env, key: &DataKey, value: bool) {
+    if value {
+        set_persistent(env, key, &true);
+    } else {
+        env.storage().persistent().remove(key);
+    }
+}
+
+/// Only scheduled auctions have a start time, other auctions start on creation
+pub fn get_start_time(env: &Env, auction_id: u64) -> Option<u64> {
+    get_persistent(env, &DataKey::StartTime(auction_id))
+}
+
+pub fn set_start_time(env: &Env, auction_id: u64, start_time: u64) {
+    set_persistent(env, &DataKey::StartTime(auction_id), &start_time);
+}
+
+pub fn get_total_extension(env: &Env, auction_id: u64) -> u64 {
+    get_persistent(env, &DataKey::TotalExtension(auction_id)).unwrap_or(0u64)
+}
+
+pub fn set_total_extension(env: &Env, auction_id: u64, total_extension: u64) {
+    set_persistent(env, &DataKey::TotalExtension(auction_id), &total_extension);
+}
+
+pub fn get_bid_increment(env: &Env, auction_id: u64) -> Result<BidIncrement, ContractError> {
+    match get_persistent(env, &DataKey::BidIncrement(auction_id)) {
+        Some(bid_increment) => Ok(bid_increment),
+        None => Ok(get_config(env)?.min_bid_increment),
+    }
+}
+
+pub fn set_bid_increment(env: &Env, auction_id: u64, bid_increment: &BidIncrement) {
+    set_persistent(env, &DataKey::BidIncrement(auction_id), bid_increment);
+}
+
+pub fn get_dutch_auction(env: &Env, auction_id: u64) -> Option<DutchAuctionInfo> {
+    get_persistent(env, &DataKey::DutchAuction(auction_id))
+}
+
+pub fn save_dutch_auction(env: &Env, auction_id: u64, dutch_auction: &DutchAuctionInfo) {
+    set_persistent(env, &DataKey::DutchAuction(auction_id), dutch_auction);
+}
+
+pub fn get_sealed_auction(env: &Env, auction_id: u64) -> Option<SealedAuctionInfo> {
+    get_persistent(env, &DataKey::SealedAuction(auction_id))
+}
+
+pub fn save_sealed_auction(env: &Env, auction_id: u64, sealed_auction: &SealedAuctionInfo) {
+    set_persistent(env, &DataKey::SealedAuction(auction_id), sealed_auction);
+}
+
+pub fn get_sealed_bid(env: &Env, auction_id: u64, bidder: &Address) -> Option<SealedBid> {
+    get_persistent(env, &DataKey::SealedBid(auction_id, bidder.clone()))
+}
+
+pub fn save_sealed_bid(env: &Env, auction_id: u64, bidder: &Address, sealed_bid: &SealedBid) {
+    let key = DataKey::SealedBid(auction_id, bidder.clone());
+
+    // keep track of the bidders, so that the auction can be settled without knowing them upfront
+    if !env.storage().persistent().has(&key) {
+        let mut bidders = get_sealed_bidders(env, auction_id);
+        bidders.push_back(bidder.clone());
+        set_persistent(env, &DataKey::SealedBidders(auction_id), &bidders);
+    }
+
+    set_persistent(env, &key, sealed_bid);
+}
+
+pub fn get_sealed_bidders(env: &Env, auction_id: u64) -> Vec<Address> {
+    get_persistent(env, &DataKey::SealedBidders(auction_id)).unwrap_or(vec![env])
+}
+
+pub fn generate_listing_id(env: &Env) -> u64 {
+    let id = env
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKey::ListingId)
+        .unwrap_or_default()
+        + 1u64;
+    env.storage().instance().set(&DataKey::ListingId, &id);
+    env.storage()
+        .instance()
+        .extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT);
+
+    id
+}
+
+pub fn get_listing_by_id(env: &Env, listing_id: u64) -> Result<Listing, ContractError> {
+    get_persistent(env, &DataKey::Listing(listing_id)).ok_or_else(|| {
+        log!(env, "Auction: Get listing by id: Listing not present");
+        ContractError::ListingNotFound
+    })
+}
+
+pub fn save_listing(env: &Env, listing: &Listing) {
+    let key = DataKey::Listing(listing.id);
+    let previous: Option<Listing> = get_persistent(env, &key);
+
+    if previous.is_none() {
+        push_to_list(
+            env,
+            &ListKey::SellerListings(listing.seller.clone()),
+            &listing.id,
+        );
+    }
+
+    let was_active = previous.is_some_and(|previous| previous.status == ListingStatus::Active);
+    let is_active = listing.status == ListingStatus::Active;
+    if was_active != is_active {
+        update_counter(
+            env,
+            &DataKey::ActiveListings(
+                listing.seller.clone(),
+                listing.collection_addr.clone(),
+                listing.item_id,
+            ),
+            is_active,
+        );
+    }
+
+    set_persistent(env, &key, listing);
+}
+
+/// Adds one to the counter under `key` when `increment` is set, removes one otherwise
+fn update_counter(env: &Env, key: &DataKey, increment: bool) {
+    let count: u64 = get_persistent(env, key).unwrap_or_default();
+    let count = if increment {
+        count + 1
+    } else {
+        count.saturating_sub(1)
+    };
+
+    if count == 0 {
+        env.storage().persistent().remove(key);
+    } else {
+        set_persistent(env, key, &count);
+    }
+}
+
+/// Whether `seller` still has active listings of the item, which need the marketplace approval
+pub fn has_active_listings(
+    env: &Env,
+    seller: &Address,
+    collection_addr: &Address,
+    item_id: u64,
+) -> bool {
+    get_persistent::<u64>(
+        env,
+        &DataKey::ActiveListings(seller.clone(), collection_addr.clone(), item_id),
+    )
+    .is_some_and(|active_listings| active_listings > 0)
+}
+
+/// Returns up to `limit` listings of `seller`, starting with the listing at `start_index`, 0 being
+/// the oldest one.
+pub fn get_listings_by_seller(
+    env: &Env,
+    seller: &Address,
+    start_index: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<Listing>, ContractError> {
+    let mut listings = vec![env];
+
+    for listing_id in get_list_page::<u64>(
+        env,
+        &ListKey::SellerListings(seller.clone()),
+        start_index,
+        limit,
+    )
+    .iter()
+    {
+        listings.push_back(get_listing_by_id(env, listing_id)?);
+    }
+
+    Ok(listings)
+}
+
+pub fn get_listings(
+    env: &Env,
+    start_index: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<Listing>, ContractError> {
+    let start_index = start_index.unwrap_or(DEFAULT_INDEX);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    let current_highest_id: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::ListingId)
+        .unwrap_or_default();
+
+    let last_index = start_index
+        .saturating_add(limit)
+        .saturating_sub(1)
+        .min(current_highest_id);
+
+    let mut listings = vec![env];
+
+    for id in start_index..=last_index {
+        listings.push_back(get_listing_by_id(env, id)?);
+    }
+
+    Ok(listings)
+}
+
+pub fn generate_offer_id(env: &Env) -> u64 {
+    let id = env
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKey::OfferId)
+        .unwrap_or_default()
+        + 1u64;
+    env.storage().instance().set(&DataKey::OfferId, &id);
+    env.storage()
+        .instance()
+        .extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT);
+
+    id
+}
+
+pub fn get_offer_by_id(env: &Env, offer_id: u64) -> Result<Offer, ContractError> {
+    get_persistent(env, &DataKey::Offer(offer_id)).ok_or_else(|| {
+        log!(env, "Auction: Get offer by id: Offer not present");
+        ContractError::OfferNotFound
+    })
+}
+
+pub fn save_offer(env: &Env, offer: &Offer) {
+    let key = DataKey::Offer(offer.id);
+
+    if !env.storage().persistent().has(&key) {
+        push_to_list(env, &ListKey::BuyerOffers(offer.buyer.clone()), &offer.id);
+        push_to_list(
+            env,
+            &ListKey::CollectionOffers(offer.item.collection_addr.clone()),
+            &offer.id,
+        );
+    }
+
+    set_persistent(env, &key, offer);
+}
+
+pub fn get_offers_by_buyer(
+    env: &Env,
+    buyer: &Address,
+    start_index: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<Offer>, ContractError> {
+    get_offers_by_list(
+        env,
+        &ListKey::BuyerOffers(buyer.clone()),
+        start_index,
+        limit,
+    )
+}
+
+pub fn get_offers_by_collection(
+    env: &Env,
+    collection_addr: &Address,
+    start_index: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<Offer>, ContractError> {
+    get_offers_by_list(
+        env,
+        &ListKey::CollectionOffers(collection_addr.clone()),
+        start_index,
+        limit,
+    )
+}
+
+fn get_offers_by_list(
+    env: &Env,
+    list: &ListKey,
+    start_index: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Vec<Offer>, ContractError> {
+    let mut offers = vec![env];
+    for offer_id in get_list_page::<u64>(env, list, start_index, limit).iter() {
+        offers.push_back(get_offer_by_id(env, offer_id)?);
+    }
+
+    Ok(offers)
+}
+
+/// Whether `seller` still has live auctions of the item created before items were escrowed, which
+/// need the marketplace approval
+pub fn has_unescrowed_auctions(
+    env: &Env,
+    seller: &Address,
+    collection_addr: &Address,
+    item_id: u64,
+) -> bool {
+    get_persistent::<u64>(
+        env,
+        &DataKey::UnescrowedAuctions(seller.clone(), collection_addr.clone(), item_id),
+    )
+    .is_some_and(|unescrowed_auctions| unescrowed_auctions > 0)
+}
+
+fn update_unescrowed_auctions(env: &Env, auction: &Auction, increment: bool) {
+    update_counter(
+        env,
+        &DataKey::UnescrowedAuctions(
+            auction.seller.clone(),
+            auction.item_info.collection_addr.clone(),
+            auction.item_info.item_id,
+        ),
+        increment,
+    );
+}
+
+/// Auctions created before items were escrowed leave the item with the seller
+pub fn is_item_escrowed(env: &Env, auction_id: u64) -> bool {
+    get_persistent(env, &DataKey::EscrowedItem(auction_id)).unwrap_or(false)
+}
+
+pub fn set_item_escrowed(env: &Env, auction_id: u64) {
+    set_persistent(env, &DataKey::EscrowedItem(auction_id), &true);
+}
+
+pub fn get_bundle_items(env: &Env, auction_id: u64) -> Option<Vec<BundleItem>> {
+    get_persistent(env, &DataKey::BundleItems(auction_id))
+}
+
+pub fn save_bundle_items(env: &Env, auction_id: u64, bundle_items: &Vec<BundleItem>) {
+    set_persistent(env, &DataKey::BundleItems(auction_id), bundle_items);
+    index_bundle_items(env, auction_id, bundle_items);
+}
+
+/// Bundle auctions are found through each of the collections and items they sell
+fn index_bundle_items(env: &Env, auction_id: u64, bundle_items: &Vec<BundleItem>) {
+    for item in bundle_items.iter() {
+        add_to_id_index(
+            env,
+            &IndexKey::CollectionAuctions(item.collection_addr.clone()),
+            auction_id,
+        );
+        add_to_id_index(
+            env,
+            &IndexKey::ItemAuctions(item.collection_addr, item.item_id),
+            auction_id,
+        );
+    }
+}
+
+/// Moves an auction and its highest bid from instance storage, where they were kept before
+/// auctions got their own persistent entries. Returns `false` when there is nothing to migrate.
+pub fn migrate_auction(env: &Env, auction_id: u64) -> bool {
+    let Some(auction) = env.storage().instance().get::<_, Auction>(&auction_id) else {
+        return false;
+    };
+
+    save_auction_by_id(env, auction_id, &auction).ok();
+    save_auction_by_seller(env, &auction.seller, &auction).ok();
+    // the item stays with the seller, so the marketplace approval is kept while the auction is live
+    if auction.status == AuctionStatus::Active || auction.status == AuctionStatus::Paused {
+        update_unescrowed_auctions(env, &auction, true);
+    }
+    env.storage().instance().remove(&auction_id);
+    // the list of auctions of the seller is replaced by the persistent id index
+    env.storage().instance().remove(&auction.seller);
+
+    let highest_bid_key = DataKey::HighestBid(auction_id);
+    if let Some(highest_bid) = env
+        .storage()
+        .instance()
+        .get::<_, HighestBid>(&highest_bid_key)
+    {
+        set_persistent(env, &highest_bid_key, &highest_bid);
+        env.storage().instance().remove(&highest_bid_key);
+        add_to_id_index(
+            env,
+            &IndexKey::BidderAuctions(highest_bid.bidder),
+            auction_id,
+        );
+    }
+
+    true
+}
+
+fn push_to_list<V: IntoVal<Env, Val>>(env: &Env, list: &ListKey, value: &V) {
+    let length_key = DataKey::ListLength(list.clone());
+    let length: u64 = get_persistent(env, &length_key).unwrap_or_default();
+
+    set_persistent(env, &DataKey::ListEntry(list.clone(), length), value);
+    set_persistent(env, &length_key, &(length + 1));
+}
+
+fn get_list_length(env: &Env, list: &ListKey) -> u64 {
+    get_persistent(env, &DataKey::ListLength(list.clone())).unwrap_or_default()
+}
+
+/// Returns up to `limit` entries of `list` starting at position `start_index`, 0 by default
+fn get_list_page<V>(
+    env: &Env,
+    list: &ListKey,
+    start_index: Option<u64>,
+    limit: Option<u64>,
+) -> Vec<V>
+where
+    V: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    let length = get_list_length(env, list);
+    let start_index = start_index.unwrap_or_default().min(length);
+    let end_index = start_index
+        .saturating_add(limit.unwrap_or(DEFAULT_LIMIT))
+        .min(length);
+
+    let mut entries = vec![env];
+    for position in start_index..end_index {
+        if let Some(entry) = get_persistent(env, &DataKey::ListEntry(list.clone(), position)) {
+            entries.push_back(entry);
+        }
+    }
+
+    entries
+}
+
+/// Reads a persistent entry and extends its TTL when present
+fn get_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+
+    if value.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+    }
+
+    value
+}
+
+fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
 }
 
 pub fn save_config(env: &Env, config: Config) {
@@ -304,6 +1600,86 @@ pub fn get_config(env: &Env) -> Result<Config, ContractError> {
     Ok(config)?
 }
 
+/// Returns the config when it is still saved in the legacy layout
+pub fn get_legacy_config(env: &Env) -> Option<LegacyConfig> {
+    let config: Map<Symbol, Val> = env.storage().persistent().get(&DataKey::Config)?;
+
+    // the creation fee moved to the accepted tokens, only the legacy layout still has it
+    if !config.contains_key(Symbol::new(env, "auction_creation_fee")) {
+        return None;
+    }
+
+    LegacyConfig::try_from_val(env, &config.to_val()).ok()
+}
+
+pub fn get_accepted_tokens(env: &Env) -> Vec<AcceptedToken> {
+    let accepted_tokens = env
+        .storage()
+        .persistent()
+        .get(&DataKey::AcceptedTokens)
+        .unwrap_or(vec![env]);
+
+    env.storage()
+        .persistent()
+        .has(&DataKey::AcceptedTokens)
+        .then(|| {
+            env.storage().persistent().extend_ttl(
+                &DataKey::AcceptedTokens,
+                LIFETIME_THRESHOLD,
+                BUMP_AMOUNT,
+            )
+        });
+
+    accepted_tokens
+}
+
+pub fn save_accepted_tokens(env: &Env, accepted_tokens: &Vec<AcceptedToken>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::AcceptedTokens, accepted_tokens);
+    env.storage().persistent().extend_ttl(
+        &DataKey::AcceptedTokens,
+        LIFETIME_THRESHOLD,
+        BUMP_AMOUNT,
+    );
+}
+
+pub fn get_accepted_token(env: &Env, token: &Address) -> Result<AcceptedToken, ContractError> {
+    get_accepted_tokens(env)
+        .iter()
+        .find(|accepted_token| &accepted_token.token == token)
+        .ok_or_else(|| {
+            log!(env, "Auction: Get accepted token: Token not accepted");
+            ContractError::TokenNotAccepted
+        })
+}
+
+pub fn get_accrued_fees(env: &Env, token: &Address) -> u128 {
+    let data_key = DataKey::AccruedFees(token.clone());
+    let accrued_fees = env.storage().persistent().get(&data_key).unwrap_or(0u128);
+
+    env.storage().persistent().has(&data_key).then(|| {
+        env.storage()
+            .persistent()
+            .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT)
+    });
+
+    accrued_fees
+}
+
+pub fn save_accrued_fees(env: &Env, token: &Address, amount: u128) {
+    let data_key = DataKey::AccruedFees(token.clone());
+    env.storage().persistent().set(&data_key, &amount);
+    env.storage()
+        .persistent()
+        .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+}
+
+pub fn accrue_fees(env: &Env, token: &Address, amount: u128) {
+    let accrued_fees = get_accrued_fees(env, token);
+    save_accrued_fees(env, token, accrued_fees + amount);
+}
+
 #[cfg(test)]
 mod test {
     use soroban_sdk::Env;
@@ -1,4 +1,24 @@
+mod anti_sniping;
+mod bid_history;
+mod bid_increment;
 mod bids;
+mod bundle_auction;
+mod cancellation;
+mod collection_verification;
+mod discovery;
+mod dutch_auction;
+mod escrow;
+mod fees;
 mod finalize_auction;
 mod initialization;
+mod listings;
+mod migration;
+mod moderation;
+mod offers;
+mod payment_tokens;
+mod private_auction;
+mod refunds;
+mod royalties;
+mod scheduled_auction;
+mod sealed_auction;
 mod setup;
@@ -0,0 +1,136 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{AntiSnipingConfig, ItemInfo},
+    test::setup::{deploy_token_contract, generate_marketplace_and_collection_client, WEEKLY},
+};
+
+const TEN_MINUTES: u64 = 600;
+
+#[test]
+fn late_bids_should_extend_the_auction_up_to_the_limit() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder_a = Address::generate(&env);
+    let bidder_b = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder_a, &1_000);
+    token_client.mint(&bidder_b, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    mp_client.update_config(
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(AntiSnipingConfig {
+            extension_window: TEN_MINUTES,
+            extension_duration: TEN_MINUTES,
+            max_total_extension: 3 * TEN_MINUTES,
+        }),
+        &None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    // bids outside of the window do not extend the auction
+    mp_client.place_bid(&1, &bidder_a, &10);
+    assert_eq!(mp_client.get_auction(&1).end_time, WEEKLY);
+
+    // 100 seconds before the end, the auction is extended to end 10 minutes after the bid
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY - 100);
+    mp_client.place_bid(&1, &bidder_b, &20);
+    assert_eq!(mp_client.get_auction(&1).end_time, WEEKLY + 500);
+
+    // the original end time has passed, but the auction is still running
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+    assert_eq!(
+        mp_client.try_finalize_auction(&1),
+        Err(Ok(ContractError::AuctionNotFinished))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 400);
+    mp_client.place_bid(&1, &bidder_a, &30);
+    assert_eq!(mp_client.get_auction(&1).end_time, WEEKLY + 1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 999);
+    mp_client.place_bid(&1, &bidder_b, &40);
+    assert_eq!(mp_client.get_auction(&1).end_time, WEEKLY + 1_599);
+
+    // the auction cannot be extended by more than 30 minutes in total
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1_500);
+    mp_client.place_bid(&1, &bidder_a, &50);
+    assert_eq!(mp_client.get_auction(&1).end_time, WEEKLY + 3 * TEN_MINUTES);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1_700);
+    mp_client.place_bid(&1, &bidder_b, &60);
+    assert_eq!(mp_client.get_auction(&1).end_time, WEEKLY + 3 * TEN_MINUTES);
+
+    env.ledger()
+        .with_mut(|li| li.timestamp = WEEKLY + 3 * TEN_MINUTES + 1);
+    assert_eq!(
+        mp_client.try_place_bid(&1, &bidder_a, &70),
+        Err(Ok(ContractError::AuctionNotActive))
+    );
+
+    mp_client.finalize_auction(&1);
+    assert_eq!(collection_client.balance_of(&bidder_b, &1), 1);
+}
+
+#[test]
+fn late_bids_should_not_extend_the_auction_by_default() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY - 1);
+    mp_client.place_bid(&1, &bidder, &10);
+    assert_eq!(mp_client.get_auction(&1).end_time, WEEKLY);
+}
@@ -0,0 +1,143 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{Bid, ItemInfo},
+    test::setup::{
+        create_multiple_auctions, deploy_token_contract,
+        generate_marketplace_and_collection_client, FOUR_HOURS,
+    },
+};
+
+#[test]
+fn bids_should_be_recorded_per_auction_and_per_bidder() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder_a = Address::generate(&env);
+    let bidder_b = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &20);
+    token_client.mint(&bidder_a, &100);
+    token_client.mint(&bidder_b, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    create_multiple_auctions(&mp_client, &seller, &collection_client, 2);
+
+    mp_client.place_bid(&1, &bidder_a, &10);
+    env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS);
+    mp_client.place_bid(&1, &bidder_b, &20);
+    mp_client.place_bid(&2, &bidder_a, &5);
+    env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS * 2);
+    mp_client.place_bid(&1, &bidder_a, &30);
+
+    // rejected bids are not recorded
+    assert_eq!(
+        mp_client.try_place_bid(&1, &bidder_b, &30),
+        Err(Ok(ContractError::BidNotEnough))
+    );
+
+    let bid = |auction_id, bidder: &Address, amount, timestamp| Bid {
+        auction_id,
+        bidder: bidder.clone(),
+        amount,
+        timestamp,
+    };
+
+    assert_eq!(
+        mp_client.get_bids(&1, &None, &None),
+        vec![
+            &env,
+            bid(1, &bidder_a, 10, 0),
+            bid(1, &bidder_b, 20, FOUR_HOURS),
+            bid(1, &bidder_a, 30, FOUR_HOURS * 2),
+        ]
+    );
+    assert_eq!(
+        mp_client.get_bids_by_bidder(&bidder_a, &None, &None),
+        vec![
+            &env,
+            bid(1, &bidder_a, 10, 0),
+            bid(2, &bidder_a, 5, FOUR_HOURS),
+            bid(1, &bidder_a, 30, FOUR_HOURS * 2),
+        ]
+    );
+    assert_eq!(
+        mp_client.get_bids_by_bidder(&bidder_b, &None, &None),
+        vec![&env, bid(1, &bidder_b, 20, FOUR_HOURS)]
+    );
+}
+
+#[test]
+fn bid_history_should_be_paginated() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &FOUR_HOURS, &None, &None);
+
+    for amount in 1..=5 {
+        mp_client.place_bid(&1, &bidder, &(amount * 10));
+    }
+
+    let amounts = |bids: soroban_sdk::Vec<Bid>| {
+        let mut amounts = vec![&env];
+        for bid in bids.iter() {
+            amounts.push_back(bid.amount);
+        }
+        amounts
+    };
+
+    assert_eq!(
+        amounts(mp_client.get_bids(&1, &None, &Some(2))),
+        vec![&env, 10, 20]
+    );
+    assert_eq!(
+        amounts(mp_client.get_bids(&1, &Some(2), &Some(2))),
+        vec![&env, 30, 40]
+    );
+    assert_eq!(
+        amounts(mp_client.get_bids_by_bidder(&bidder, &Some(3), &None)),
+        vec![&env, 40, 50]
+    );
+    assert!(mp_client.get_bids(&1, &Some(10), &None).is_empty());
+
+    assert_eq!(
+        mp_client.try_get_bids(&2, &None, &None),
+        Err(Ok(ContractError::AuctionNotFound))
+    );
+}
@@ -0,0 +1,198 @@
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+use crate::{
+    error::ContractError,
+    storage::{BidIncrement, ItemInfo},
+    test::setup::{deploy_token_contract, generate_marketplace_and_collection_client, WEEKLY},
+};
+
+#[test]
+fn bids_should_respect_the_configured_increment() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder_a = Address::generate(&env);
+    let bidder_b = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder_a, &1_000);
+    token_client.mint(&bidder_b, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    // 5 tokens or 10% of the highest bid, whichever is larger
+    mp_client.update_config(
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(BidIncrement {
+            absolute: 5,
+            bps: 1_000,
+        }),
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    assert_eq!(mp_client.get_min_next_bid(&1), 1);
+    mp_client.place_bid(&1, &bidder_a, &10);
+
+    // the absolute increment is larger
+    assert_eq!(mp_client.get_min_next_bid(&1), 15);
+    assert_eq!(
+        mp_client.try_place_bid(&1, &bidder_b, &14),
+        Err(Ok(ContractError::BidIncrementNotMet))
+    );
+    assert_eq!(
+        mp_client.try_place_bid(&1, &bidder_b, &10),
+        Err(Ok(ContractError::BidNotEnough))
+    );
+    mp_client.place_bid(&1, &bidder_b, &15);
+
+    mp_client.place_bid(&1, &bidder_a, &100);
+
+    // the relative increment is larger
+    assert_eq!(mp_client.get_min_next_bid(&1), 110);
+    assert_eq!(
+        mp_client.try_place_bid(&1, &bidder_b, &109),
+        Err(Ok(ContractError::BidIncrementNotMet))
+    );
+    mp_client.place_bid(&1, &bidder_b, &110);
+
+    assert_eq!(mp_client.get_highest_bid(&1).bid, 110);
+    assert_eq!(
+        mp_client.pending_refund(&bidder_a, &token_client.address),
+        110
+    );
+    mp_client.withdraw_refund(&bidder_a, &token_client.address);
+    assert_eq!(token_client.balance(&bidder_a), 1_000);
+}
+
+#[test]
+fn seller_should_override_the_increment_before_the_first_bid() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    assert_eq!(
+        mp_client.try_set_bid_increment(
+            &1,
+            &BidIncrement {
+                absolute: 0,
+                bps: 10_001
+            }
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    mp_client.set_bid_increment(
+        &1,
+        &BidIncrement {
+            absolute: 20,
+            bps: 0,
+        },
+    );
+
+    // updating the global config does not change already created auctions
+    mp_client.update_config(
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(BidIncrement {
+            absolute: 1,
+            bps: 0,
+        }),
+    );
+
+    mp_client.place_bid(&1, &bidder, &10);
+    assert_eq!(mp_client.get_min_next_bid(&1), 30);
+
+    assert_eq!(
+        mp_client.try_set_bid_increment(
+            &1,
+            &BidIncrement {
+                absolute: 1,
+                bps: 0
+            }
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+}
+
+#[test]
+fn update_config_should_reject_invalid_increment() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+
+    let (mp_client, _) =
+        generate_marketplace_and_collection_client(&env, &admin, &token_client.address, None, None);
+
+    assert_eq!(
+        mp_client.try_update_config(
+            &None,
+            &None,
+            &None,
+            &None,
+            &None,
+            &Some(BidIncrement {
+                absolute: 0,
+                bps: 10_001,
+            }),
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+    assert_eq!(
+        mp_client.get_config().min_bid_increment,
+        BidIncrement {
+            absolute: 0,
+            bps: 0
+        }
+    );
+}
@@ -1,6 +1,6 @@
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    vec, Address, Env,
+    vec, Address, Bytes, Env,
 };
 
 use crate::{
@@ -8,7 +8,8 @@ use crate::{
     error::ContractError,
     storage::{Auction, AuctionStatus, HighestBid, ItemInfo},
     test::setup::{
-        deploy_token_contract, generate_marketplace_and_collection_client, DAY, FOUR_HOURS, WEEKLY,
+        deploy_token_contract, generate_marketplace_and_collection_client, withdraw_refunds, DAY,
+        FOUR_HOURS, WEEKLY,
     },
 };
 
@@ -18,7 +19,7 @@ use super::setup::create_and_initialize_collection;
 fn should_place_a_bid() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
     let seller = Address::generate(&env);
     let bidder_a = Address::generate(&env);
     let bidder_b = Address::generate(&env);
@@ -45,7 +46,7 @@ fn should_place_a_bid() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     mp_client.place_bid(&1, &bidder_a, &10);
     assert_eq!(
@@ -66,6 +67,13 @@ fn should_place_a_bid() {
             bidder: bidder_b.clone()
         }
     );
+    // the outbid amount is credited and withdrawn by the bidder
+    assert_eq!(token_client.balance(&bidder_a), 0i128);
+    assert_eq!(
+        mp_client.pending_refund(&bidder_a, &token_client.address),
+        10
+    );
+    mp_client.withdraw_refund(&bidder_a, &token_client.address);
     assert_eq!(token_client.balance(&mp_client.address), 30i128);
     assert_eq!(token_client.balance(&bidder_a), 10i128);
     assert_eq!(token_client.balance(&bidder_b), 0i128);
@@ -95,6 +103,7 @@ fn should_place_a_bid() {
             bidder: bidder_c.clone()
         }
     );
+    mp_client.withdraw_refund(&bidder_b, &token_client.address);
     assert_eq!(token_client.balance(&mp_client.address), 50i128);
     assert_eq!(token_client.balance(&bidder_a), 10i128);
     assert_eq!(token_client.balance(&bidder_b), 20i128);
@@ -105,7 +114,7 @@ fn should_place_a_bid() {
 fn fail_to_place_bid_when_auction_inactive() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
     let seller = Address::generate(&env);
     let bidder_a = Address::generate(&env);
 
@@ -119,7 +128,7 @@ fn fail_to_place_bid_when_auction_inactive() {
         None,
         None,
     );
-    nft_collection_client.set_approval_for_all(&mp_client.address, &true);
+    nft_collection_client.set_approval_for_all(&seller, &mp_client.address, &true);
 
     let item_info = ItemInfo {
         collection_addr: nft_collection_client.address.clone(),
@@ -129,7 +138,7 @@ fn fail_to_place_bid_when_auction_inactive() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     env.ledger().with_mut(|li| li.timestamp = WEEKLY + DAY);
 
@@ -145,7 +154,7 @@ fn fail_to_place_bid_when_auction_inactive() {
 fn seller_tries_to_place_a_bid_should_fail() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let seller = Address::generate(&env);
 
@@ -169,7 +178,7 @@ fn seller_tries_to_place_a_bid_should_fail() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     env.ledger().with_mut(|li| li.timestamp = DAY);
 
@@ -183,7 +192,7 @@ fn seller_tries_to_place_a_bid_should_fail() {
 fn buy_now_should_fail_when_auction_not_active() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -212,12 +221,12 @@ fn buy_now_should_fail_when_auction_not_active() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &DAY);
+    mp_client.create_auction(&item_info, &seller, &DAY, &None, &None);
 
     env.ledger().with_mut(|li| li.timestamp = WEEKLY);
 
     assert_eq!(
-        mp_client.try_buy_now(&1, &fomo_buyer),
+        mp_client.try_buy_now(&1, &fomo_buyer, &1),
         Err(Ok(ContractError::AuctionNotActive))
     );
 }
@@ -226,7 +235,7 @@ fn buy_now_should_fail_when_auction_not_active() {
 fn buy_now_should_fail_when_no_buy_now_price_has_been_set() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -255,10 +264,10 @@ fn buy_now_should_fail_when_no_buy_now_price_has_been_set() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &DAY);
+    mp_client.create_auction(&item_info, &seller, &DAY, &None, &None);
 
     assert_eq!(
-        mp_client.try_buy_now(&1, &fomo_buyer),
+        mp_client.try_buy_now(&1, &fomo_buyer, &1),
         Err(Ok(ContractError::NoBuyNowOption))
     );
 }
@@ -267,7 +276,7 @@ fn buy_now_should_fail_when_no_buy_now_price_has_been_set() {
 fn buy_now() {
     let env = Env::default();
     env.mock_all_auths_allowing_non_root_auth();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -292,7 +301,7 @@ fn buy_now() {
 
     collections_client.mint(&seller, &seller, &1, &5);
 
-    collections_client.set_approval_for_transfer(&mp_client.address, &1u64, &true);
+    collections_client.set_approval_for_transfer(&seller, &mp_client.address, &1u64, &true);
 
     let item_info = ItemInfo {
         collection_addr: collections_client.address.clone(),
@@ -302,7 +311,7 @@ fn buy_now() {
         amount: 5,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     // 4 hours in and we have a first highest bid
     env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS);
@@ -313,6 +322,7 @@ fn buy_now() {
     // 8 hours in and we have a second highest bid
     env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS * 2);
     mp_client.place_bid(&1, &bidder_b, &10);
+    mp_client.withdraw_refund(&bidder_a, &token_client.address);
     assert_eq!(token_client.balance(&bidder_a), 100);
     assert_eq!(token_client.balance(&bidder_b), 90);
     assert_eq!(token_client.balance(&mp_client.address), 20);
@@ -320,6 +330,7 @@ fn buy_now() {
     // 16 hours in and we have a third highest bid
     env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS * 4);
     mp_client.place_bid(&1, &fomo_buyer, &25);
+    mp_client.withdraw_refund(&bidder_b, &token_client.address);
     assert_eq!(token_client.balance(&bidder_a), 100);
     assert_eq!(token_client.balance(&bidder_b), 100);
     assert_eq!(token_client.balance(&fomo_buyer), 75);
@@ -328,6 +339,7 @@ fn buy_now() {
     // 24 hours in and we have a 4th highest bid
     env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS * 6);
     mp_client.place_bid(&1, &bidder_b, &30);
+    mp_client.withdraw_refund(&fomo_buyer, &token_client.address);
     assert_eq!(token_client.balance(&bidder_a), 100);
     assert_eq!(token_client.balance(&bidder_b), 70);
     assert_eq!(token_client.balance(&fomo_buyer), 100);
@@ -336,20 +348,36 @@ fn buy_now() {
     // 36 hours in and we have a 5th highest bid, which is over the buy now price
     env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS * 9);
     mp_client.place_bid(&1, &bidder_a, &60);
+    mp_client.withdraw_refund(&bidder_b, &token_client.address);
     assert_eq!(token_client.balance(&bidder_a), 40);
     assert_eq!(token_client.balance(&bidder_b), 100);
     assert_eq!(token_client.balance(&fomo_buyer), 100);
     assert_eq!(token_client.balance(&mp_client.address), 70);
 
-    // 40 hours in and the fomo buyer sees the previous user mistake and buys now
+    // 40 hours in and the fomo buyer sees the previous user mistake, but a standing bid is on the
+    // whole lot, so it cannot be bought in parts
     env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS * 10);
-    mp_client.buy_now(&1, &fomo_buyer);
+    assert_eq!(
+        mp_client.try_buy_now(&1, &fomo_buyer, &1),
+        Err(Ok(ContractError::PartialBuyWithBids))
+    );
+
+    // so the whole lot is bought now
+    token_client.mint(&fomo_buyer, &200);
+    mp_client.buy_now(&1, &fomo_buyer, &5);
+    mp_client.withdraw_refund(&bidder_a, &token_client.address);
+    // the proceeds wait in the marketplace until the seller withdraws them
+    assert_eq!(
+        mp_client.pending_refund(&seller, &token_client.address),
+        250
+    );
+    mp_client.withdraw_refund(&seller, &token_client.address);
     assert_eq!(token_client.balance(&bidder_a), 100);
     assert_eq!(token_client.balance(&bidder_b), 100);
     assert_eq!(token_client.balance(&fomo_buyer), 50);
     // mp_client has the fees from the auction creation
     assert_eq!(token_client.balance(&mp_client.address), 10);
-    assert_eq!(token_client.balance(&seller), 50);
+    assert_eq!(token_client.balance(&seller), 250);
 
     assert_eq!(
         mp_client.get_auction(&1),
@@ -357,21 +385,94 @@ fn buy_now() {
             id: 1,
             item_info,
             seller,
-            highest_bid: Some(50),
+            highest_bid: Some(250),
             end_time: WEEKLY,
             status: AuctionStatus::Ended,
             auction_token: token_client.address
         }
     );
 
-    assert_eq!(collections_client.balance_of(&fomo_buyer, &1), 1);
+    assert_eq!(collections_client.balance_of(&fomo_buyer, &1), 5);
+    assert_eq!(collections_client.balance_of(&mp_client.address, &1), 0);
+}
+
+#[test]
+fn buy_now_should_sell_the_lot_in_parts_until_sold_out() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let buyer_a = Address::generate(&env);
+    let buyer_b = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&buyer_a, &100);
+    token_client.mint(&buyer_b, &100);
+
+    let (mp_client, collections_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    collections_client.mint(&seller, &seller, &1, &3);
+
+    let item_info = ItemInfo {
+        collection_addr: collections_client.address.clone(),
+        item_id: 1,
+        minimum_price: Some(50),
+        buy_now_price: Some(20),
+        amount: 5,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    assert_eq!(
+        mp_client.try_buy_now(&1, &buyer_a, &0),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+    assert_eq!(
+        mp_client.try_buy_now(&1, &buyer_a, &6),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    mp_client.buy_now(&1, &buyer_a, &2);
+    assert_eq!(token_client.balance(&buyer_a), 60);
+    assert_eq!(collections_client.balance_of(&buyer_a, &1), 2);
+    assert_eq!(mp_client.get_auction(&1).item_info.amount, 3);
+    assert_eq!(mp_client.get_auction(&1).status, AuctionStatus::Active);
+    // the reserve price follows the size of the lot
+    assert_eq!(mp_client.get_auction(&1).item_info.minimum_price, Some(30));
+
+    // cannot buy more than what is left
+    assert_eq!(
+        mp_client.try_buy_now(&1, &buyer_b, &4),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    mp_client.buy_now(&1, &buyer_b, &3);
+    assert_eq!(token_client.balance(&buyer_b), 40);
+    assert_eq!(collections_client.balance_of(&buyer_b, &1), 3);
+    assert_eq!(collections_client.balance_of(&mp_client.address, &1), 0);
+    mp_client.withdraw_refund(&seller, &token_client.address);
+    assert_eq!(token_client.balance(&seller), 100);
+
+    let auction = mp_client.get_auction(&1);
+    assert_eq!(auction.status, AuctionStatus::Ended);
+    assert_eq!(auction.highest_bid, Some(60));
+    assert_eq!(
+        mp_client.try_buy_now(&1, &buyer_a, &1),
+        Err(Ok(ContractError::AuctionNotActive))
+    );
 }
 
 #[test]
 fn pause_changes_status_and_second_attempt_fails_to_pause() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -397,7 +498,7 @@ fn pause_changes_status_and_second_attempt_fails_to_pause() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     env.ledger().with_mut(|li| li.timestamp = DAY);
 
@@ -424,7 +525,7 @@ fn pause_changes_status_and_second_attempt_fails_to_pause() {
 fn pause_after_enddate_should_fail() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -450,7 +551,7 @@ fn pause_after_enddate_should_fail() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     env.ledger().with_mut(|li| li.timestamp = WEEKLY + DAY);
 
@@ -464,7 +565,7 @@ fn pause_after_enddate_should_fail() {
 fn unpause_changes_status_and_second_attempt_fails_to_unpause() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -492,7 +593,7 @@ fn unpause_changes_status_and_second_attempt_fails_to_unpause() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     env.ledger().with_mut(|li| li.timestamp = DAY);
 
@@ -510,13 +611,14 @@ fn unpause_changes_status_and_second_attempt_fails_to_unpause() {
     );
 
     assert_eq!(
-        mp_client.try_buy_now(&1, &bidder),
+        mp_client.try_buy_now(&1, &bidder, &1),
         Err(Ok(ContractError::AuctionNotActive))
     );
 
+    // a paused auction is only settled once it is over
     assert_eq!(
         mp_client.try_finalize_auction(&1),
-        Err(Ok(ContractError::AuctionNotActive))
+        Err(Ok(ContractError::AuctionNotFinished))
     );
 
     mp_client.unpause(&1);
@@ -546,7 +648,7 @@ fn unpause_changes_status_and_second_attempt_fails_to_unpause() {
 fn multiple_auction_by_multiple_sellers() {
     let env = Env::default();
     env.mock_all_auths_allowing_non_root_auth();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
 
@@ -568,10 +670,9 @@ fn multiple_auction_by_multiple_sellers() {
     token_client.mint(&bidder_b, &1_000);
     token_client.mint(&bidder_c, &1_000);
 
-    let mp_client =
-        MarketplaceContractClient::new(&env, &env.register_contract(None, MarketplaceContract {}));
+    let mp_client = MarketplaceContractClient::new(&env, &env.register(MarketplaceContract, ()));
 
-    mp_client.initialize(&admin, &token_client.address, &10);
+    mp_client.initialize(&admin, &token_client.address, &10, &1_000, &0, &admin);
 
     // ============ Collections client setup ============
     let collection_a_client =
@@ -590,9 +691,10 @@ fn multiple_auction_by_multiple_sellers() {
         amount: 1,
     };
 
+    collection_a_client.create_token(&seller_a, &2, &Bytes::new(&env), &None, &true, &0);
     collection_a_client.mint(&seller_a, &seller_a, &2, &1);
 
-    mp_client.create_auction(&first_item_info_seller_a, &seller_a, &WEEKLY);
+    mp_client.create_auction(&first_item_info_seller_a, &seller_a, &WEEKLY, &None, &None);
 
     let second_item_info_seller_a = ItemInfo {
         collection_addr: collection_a_client.address.clone(),
@@ -602,7 +704,7 @@ fn multiple_auction_by_multiple_sellers() {
         amount: 1,
     };
 
-    mp_client.create_auction(&second_item_info_seller_a, &seller_a, &WEEKLY);
+    mp_client.create_auction(&second_item_info_seller_a, &seller_a, &WEEKLY, &None, &None);
 
     let item_info_seller_b = ItemInfo {
         collection_addr: collection_b_client.address.clone(),
@@ -612,7 +714,7 @@ fn multiple_auction_by_multiple_sellers() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info_seller_b, &seller_b, &WEEKLY);
+    mp_client.create_auction(&item_info_seller_b, &seller_b, &WEEKLY, &None, &None);
 
     let item_info_seller_c = ItemInfo {
         collection_addr: collection_c_client.address.clone(),
@@ -622,17 +724,19 @@ fn multiple_auction_by_multiple_sellers() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info_seller_c, &seller_c, &DAY);
+    mp_client.create_auction(&item_info_seller_c, &seller_c, &DAY, &None, &None);
     // ============ Authorized transfer ============================
-    collection_a_client.set_approval_for_transfer(&mp_client.address, &1, &true);
-    collection_a_client.set_approval_for_transfer(&mp_client.address, &2, &true);
-    collection_b_client.set_approval_for_transfer(&mp_client.address, &1, &true);
-    collection_c_client.set_approval_for_transfer(&mp_client.address, &1, &true);
+    collection_a_client.set_approval_for_transfer(&seller_a, &mp_client.address, &1, &true);
+    collection_a_client.set_approval_for_transfer(&seller_a, &mp_client.address, &2, &true);
+    collection_b_client.set_approval_for_transfer(&seller_b, &mp_client.address, &1, &true);
+    collection_c_client.set_approval_for_transfer(&seller_c, &mp_client.address, &1, &true);
 
     // ============ Assert everything is before bidding ============
 
     assert_eq!(
-        mp_client.get_auctions_by_seller(&seller_a),
+        mp_client
+            .get_auctions_by_seller(&seller_a, &None, &None)
+            .auctions,
         vec![
             &env,
             Auction {
@@ -657,7 +761,9 @@ fn multiple_auction_by_multiple_sellers() {
     );
 
     assert_eq!(
-        mp_client.get_auctions_by_seller(&seller_b),
+        mp_client
+            .get_auctions_by_seller(&seller_b, &None, &None)
+            .auctions,
         vec![
             &env,
             Auction {
@@ -673,7 +779,9 @@ fn multiple_auction_by_multiple_sellers() {
     );
 
     assert_eq!(
-        mp_client.get_auctions_by_seller(&seller_c),
+        mp_client
+            .get_auctions_by_seller(&seller_c, &None, &None)
+            .auctions,
         vec![
             &env,
             Auction {
@@ -699,6 +807,11 @@ fn multiple_auction_by_multiple_sellers() {
     mp_client.place_bid(&3, &bidder_c, &26);
     mp_client.place_bid(&4, &bidder_b, &100);
 
+    withdraw_refunds(
+        &mp_client,
+        &token_client.address,
+        &[&bidder_a, &bidder_b, &bidder_c],
+    );
     assert_eq!(token_client.balance(&bidder_a), 900);
     assert_eq!(token_client.balance(&bidder_b), 900);
     assert_eq!(token_client.balance(&bidder_c), 974);
@@ -714,6 +827,7 @@ fn multiple_auction_by_multiple_sellers() {
     );
 
     mp_client.finalize_auction(&4);
+    mp_client.withdraw_refund(&seller_c, &token_client.address);
     assert_eq!(token_client.balance(&mp_client.address), 166);
     assert_eq!(token_client.balance(&bidder_a), 900);
     assert_eq!(token_client.balance(&bidder_b), 900);
@@ -728,6 +842,11 @@ fn multiple_auction_by_multiple_sellers() {
     mp_client.place_bid(&2, &bidder_c, &75);
     mp_client.place_bid(&3, &bidder_a, &50);
 
+    withdraw_refunds(
+        &mp_client,
+        &token_client.address,
+        &[&bidder_a, &bidder_b, &bidder_c],
+    );
     // `bidder_a` has been outbid in both #1 and #2, so he gets his 100 in total back; then he
     // places a 50 bid on #3 leaving his balance with 950
     assert_eq!(token_client.balance(&bidder_a), 950);
@@ -744,7 +863,7 @@ fn multiple_auction_by_multiple_sellers() {
     env.ledger().with_mut(|li| li.timestamp = DAY * 4);
     // `bidder_b` fomos and buys the item in auction #1. Right after that `bidder_a`tries to bid on
     // that item but fails to do as the auction has ended.
-    mp_client.buy_now(&1, &bidder_b);
+    mp_client.buy_now(&1, &bidder_b, &1);
     assert_eq!(
         mp_client.try_place_bid(&1, &bidder_a, &150),
         Err(Ok(ContractError::AuctionNotActive))
@@ -769,6 +888,11 @@ fn multiple_auction_by_multiple_sellers() {
 
     // day #6
     // let's count the balances again
+    withdraw_refunds(
+        &mp_client,
+        &token_client.address,
+        &[&bidder_a, &bidder_b, &bidder_c],
+    );
 
     assert_eq!(token_client.balance(&bidder_a), 850);
     // `bidder_b` has the lowest balance, due to `buy_now`
@@ -788,7 +912,9 @@ fn multiple_auction_by_multiple_sellers() {
 
     // assertions of the state of the auctions
     assert_eq!(
-        mp_client.get_auctions_by_seller(&seller_a),
+        mp_client
+            .get_auctions_by_seller(&seller_a, &None, &None)
+            .auctions,
         vec![
             &env,
             Auction {
@@ -813,7 +939,9 @@ fn multiple_auction_by_multiple_sellers() {
     );
 
     assert_eq!(
-        mp_client.get_auctions_by_seller(&seller_b),
+        mp_client
+            .get_auctions_by_seller(&seller_b, &None, &None)
+            .auctions,
         vec![
             &env,
             Auction {
@@ -829,7 +957,9 @@ fn multiple_auction_by_multiple_sellers() {
     );
 
     assert_eq!(
-        mp_client.get_auctions_by_seller(&seller_c),
+        mp_client
+            .get_auctions_by_seller(&seller_c, &None, &None)
+            .auctions,
         vec![
             &env,
             Auction {
@@ -845,6 +975,11 @@ fn multiple_auction_by_multiple_sellers() {
     );
 
     // assertions of the token balances
+    withdraw_refunds(
+        &mp_client,
+        &token_client.address,
+        &[&bidder_a, &bidder_b, &bidder_c, &seller_a, &seller_b],
+    );
     // because `bidder_b` used `buy_now` for auction #1 and no one was able to put at least 500 as
     // bid to meet the minimum price
     assert_eq!(token_client.balance(&seller_a), 500);
@@ -902,14 +1037,14 @@ fn buy_now_should_fail_when_status_is_different_from_active() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     env.ledger().with_mut(|li| li.timestamp = DAY);
 
     mp_client.pause(&1);
 
     assert_eq!(
-        mp_client.try_buy_now(&1, &bidder),
+        mp_client.try_buy_now(&1, &bidder, &1),
         Err(Ok(ContractError::AuctionNotActive))
     );
     assert_eq!(token.balance(&bidder), 10);
@@ -919,7 +1054,7 @@ fn buy_now_should_fail_when_status_is_different_from_active() {
 fn buy_now_should_work_when_no_previous_bid() {
     let env = Env::default();
     env.mock_all_auths_allowing_non_root_auth();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -940,7 +1075,7 @@ fn buy_now_should_work_when_no_previous_bid() {
 
     collections_client.mint(&seller, &seller, &1, &1);
 
-    collections_client.set_approval_for_transfer(&mp_client.address, &1u64, &true);
+    collections_client.set_approval_for_transfer(&seller, &mp_client.address, &1u64, &true);
 
     let item_info = ItemInfo {
         collection_addr: collections_client.address.clone(),
@@ -950,11 +1085,12 @@ fn buy_now_should_work_when_no_previous_bid() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS);
 
-    mp_client.buy_now(&1, &fomo_buyer);
+    mp_client.buy_now(&1, &fomo_buyer, &1);
+    mp_client.withdraw_refund(&seller, &token_client.address);
 
     assert_eq!(token_client.balance(&fomo_buyer), 50);
     assert_eq!(token_client.balance(&mp_client.address), 10);
@@ -965,7 +1101,7 @@ fn buy_now_should_work_when_no_previous_bid() {
 fn buy_now_should_refund_previous_buyer() {
     let env = Env::default();
     env.mock_all_auths_allowing_non_root_auth();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -988,7 +1124,7 @@ fn buy_now_should_refund_previous_buyer() {
 
     collections_client.mint(&seller, &seller, &1, &1);
 
-    collections_client.set_approval_for_transfer(&mp_client.address, &1u64, &true);
+    collections_client.set_approval_for_transfer(&seller, &mp_client.address, &1u64, &true);
 
     let item_info = ItemInfo {
         collection_addr: collections_client.address.clone(),
@@ -998,7 +1134,7 @@ fn buy_now_should_refund_previous_buyer() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS);
 
@@ -1008,7 +1144,8 @@ fn buy_now_should_refund_previous_buyer() {
 
     env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS * 2);
 
-    mp_client.buy_now(&1, &fomo_buyer);
+    mp_client.buy_now(&1, &fomo_buyer, &1);
+    withdraw_refunds(&mp_client, &token_client.address, &[&bidder, &seller]);
 
     assert_eq!(token_client.balance(&fomo_buyer), 50);
     assert_eq!(token_client.balance(&bidder), 100);
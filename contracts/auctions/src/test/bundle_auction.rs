@@ -0,0 +1,251 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{AuctionStatus, BundleItem},
+    test::setup::{
+        create_and_initialize_collection, deploy_token_contract,
+        generate_marketplace_and_collection_client, withdraw_refunds, WEEKLY,
+    },
+};
+
+#[test]
+fn bundle_auction_should_deliver_every_item_to_the_winner() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    let other_collection = create_and_initialize_collection(&env, &creator, "Cats", "CAT");
+    other_collection.safe_transfer_from(&creator, &creator, &seller, &1, &3);
+    other_collection.set_default_royalty(&creator, &creator, &1_000);
+
+    let items = vec![
+        &env,
+        BundleItem {
+            collection_addr: collection_client.address.clone(),
+            item_id: 1,
+            amount: 2,
+        },
+        BundleItem {
+            collection_addr: other_collection.address.clone(),
+            item_id: 1,
+            amount: 3,
+        },
+    ];
+    let auction =
+        mp_client.create_bundle_auction(&items, &seller, &WEEKLY, &None, &Some(50), &None);
+
+    assert_eq!(mp_client.get_auction_items(&auction.id), items);
+    assert_eq!(collection_client.balance_of(&seller, &1), 0);
+    assert_eq!(other_collection.balance_of(&seller, &1), 0);
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 2);
+    assert_eq!(other_collection.balance_of(&mp_client.address, &1), 3);
+
+    mp_client.place_bid(&auction.id, &bidder, &100);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+    mp_client.finalize_auction(&auction.id);
+
+    assert_eq!(
+        mp_client.get_auction(&auction.id).status,
+        AuctionStatus::Ended
+    );
+    assert_eq!(collection_client.balance_of(&bidder, &1), 2);
+    assert_eq!(other_collection.balance_of(&bidder, &1), 3);
+
+    // royalty of the second collection is taken from its half of the price
+    assert_eq!(mp_client.pending_refund(&creator, &token_client.address), 5);
+    withdraw_refunds(&mp_client, &token_client.address, &[&creator, &seller]);
+    assert_eq!(token_client.balance(&creator), 5);
+    assert_eq!(token_client.balance(&seller), 95);
+}
+
+#[test]
+fn bundle_auction_without_sale_should_return_every_item() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &20);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    let other_collection = create_and_initialize_collection(&env, &seller, "Cats", "CAT");
+
+    let items = vec![
+        &env,
+        BundleItem {
+            collection_addr: collection_client.address.clone(),
+            item_id: 1,
+            amount: 1,
+        },
+        BundleItem {
+            collection_addr: other_collection.address.clone(),
+            item_id: 1,
+            amount: 5,
+        },
+    ];
+    let first = mp_client.create_bundle_auction(&items, &seller, &WEEKLY, &None, &None, &None);
+
+    mp_client.cancel_auction(&first.id);
+    assert_eq!(collection_client.balance_of(&seller, &1), 2);
+    assert_eq!(other_collection.balance_of(&seller, &1), 5);
+
+    let second = mp_client.create_bundle_auction(&items, &seller, &WEEKLY, &None, &None, &None);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+    mp_client.finalize_auction(&second.id);
+
+    assert_eq!(collection_client.balance_of(&seller, &1), 2);
+    assert_eq!(other_collection.balance_of(&seller, &1), 5);
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 0);
+    assert_eq!(other_collection.balance_of(&mp_client.address, &1), 0);
+}
+
+#[test]
+fn buy_now_should_deliver_the_whole_bundle() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&buyer, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    let other_collection = create_and_initialize_collection(&env, &seller, "Cats", "CAT");
+
+    let items = vec![
+        &env,
+        BundleItem {
+            collection_addr: collection_client.address.clone(),
+            item_id: 1,
+            amount: 1,
+        },
+        BundleItem {
+            collection_addr: other_collection.address.clone(),
+            item_id: 1,
+            amount: 2,
+        },
+    ];
+    let auction =
+        mp_client.create_bundle_auction(&items, &seller, &WEEKLY, &None, &None, &Some(80));
+
+    mp_client.buy_now(&auction.id, &buyer, &1);
+
+    assert_eq!(collection_client.balance_of(&buyer, &1), 1);
+    assert_eq!(other_collection.balance_of(&buyer, &1), 2);
+    assert_eq!(collection_client.balance_of(&seller, &1), 1);
+    assert_eq!(other_collection.balance_of(&seller, &1), 3);
+    mp_client.withdraw_refund(&seller, &token_client.address);
+    assert_eq!(token_client.balance(&seller), 80);
+    assert_eq!(token_client.balance(&buyer), 20);
+}
+
+#[test]
+fn create_bundle_auction_should_fail_with_invalid_items() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &50);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    let other_collection = create_and_initialize_collection(&env, &seller, "Cats", "CAT");
+
+    let first = BundleItem {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        amount: 1,
+    };
+
+    // a bundle needs at least two items
+    assert_eq!(
+        mp_client.try_create_bundle_auction(
+            &vec![&env, first.clone()],
+            &seller,
+            &WEEKLY,
+            &None,
+            &None,
+            &None
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    // the same item cannot be sold twice in a bundle
+    assert_eq!(
+        mp_client.try_create_bundle_auction(
+            &vec![&env, first.clone(), first.clone()],
+            &seller,
+            &WEEKLY,
+            &None,
+            &None,
+            &None
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    // not enough balance of one of the items rolls back the whole creation
+    let not_owned = BundleItem {
+        collection_addr: other_collection.address.clone(),
+        item_id: 1,
+        amount: 6,
+    };
+    assert_eq!(
+        mp_client.try_create_bundle_auction(
+            &vec![&env, first, not_owned],
+            &seller,
+            &WEEKLY,
+            &None,
+            &None,
+            &None
+        ),
+        Err(Ok(ContractError::NotEnoughBalance))
+    );
+    assert_eq!(collection_client.balance_of(&seller, &1), 2);
+    assert_eq!(token_client.balance(&seller), 50);
+}
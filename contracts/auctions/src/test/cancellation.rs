@@ -0,0 +1,198 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{AuctionStatus, ItemInfo},
+    test::setup::{deploy_token_contract, generate_marketplace_and_collection_client, WEEKLY},
+};
+
+#[test]
+fn seller_should_cancel_auction_without_bids() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: Some(50),
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 1);
+    assert_eq!(collection_client.balance_of(&seller, &1), 1);
+
+    mp_client.cancel_auction(&1);
+
+    assert_eq!(mp_client.get_auction(&1).status, AuctionStatus::Cancelled);
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 0);
+    assert!(!collection_client.is_approved_for_transfer(&seller, &mp_client.address, &1));
+
+    // a cancelled auction accepts no more bids, purchases or cancellations
+    assert_eq!(
+        mp_client.try_place_bid(&1, &bidder, &10),
+        Err(Ok(ContractError::AuctionNotActive))
+    );
+    assert_eq!(
+        mp_client.try_buy_now(&1, &bidder, &1),
+        Err(Ok(ContractError::AuctionNotActive))
+    );
+    assert_eq!(
+        mp_client.try_cancel_auction(&1),
+        Err(Ok(ContractError::AuctionNotActive))
+    );
+    assert_eq!(collection_client.balance_of(&seller, &1), 2);
+}
+
+#[test]
+fn cancel_auction_with_bids_should_follow_the_policy() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.place_bid(&1, &bidder, &40);
+
+    // by default auctions with bids cannot be cancelled
+    assert_eq!(
+        mp_client.try_cancel_auction(&1),
+        Err(Ok(ContractError::AuctionCannotBeCancelled))
+    );
+
+    mp_client.update_config(&None, &None, &None, &Some(true), &None, &None);
+    mp_client.pause(&1);
+    mp_client.cancel_auction(&1);
+
+    assert_eq!(mp_client.get_auction(&1).status, AuctionStatus::Cancelled);
+    assert_eq!(mp_client.pending_refund(&bidder, &token_client.address), 40);
+    mp_client.withdraw_refund(&bidder, &token_client.address);
+    assert_eq!(token_client.balance(&bidder), 100);
+    // only the auction creation fee is kept
+    assert_eq!(token_client.balance(&mp_client.address), 10);
+}
+
+#[test]
+fn cancel_auction_should_fail_when_bids_won_the_auction() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    mp_client.update_config(&None, &None, &None, &Some(true), &None, &None);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.place_bid(&1, &bidder, &40);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+
+    assert_eq!(
+        mp_client.try_cancel_auction(&1),
+        Err(Ok(ContractError::AuctionCannotBeCancelled))
+    );
+
+    mp_client.finalize_auction(&1);
+    assert_eq!(collection_client.balance_of(&bidder, &1), 1);
+}
+
+#[test]
+fn cancel_auction_should_return_only_its_own_escrowed_item() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &20);
+    token_client.mint(&buyer, &50);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: Some(50),
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 2);
+
+    mp_client.cancel_auction(&1);
+    assert_eq!(collection_client.balance_of(&seller, &1), 1);
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 1);
+
+    mp_client.buy_now(&2, &buyer, &1);
+    assert_eq!(collection_client.balance_of(&buyer, &1), 1);
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 0);
+}
@@ -0,0 +1,186 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Bytes, BytesN, Env, String};
+
+use crate::{
+    collection, deployer,
+    error::ContractError,
+    storage::{BundleItem, CollectionVerification, ItemInfo},
+    test::setup::{
+        create_and_initialize_collection, deploy_token_contract,
+        generate_marketplace_and_collection_client, WEEKLY,
+    },
+};
+
+#[test]
+fn collections_deployed_by_the_configured_deployer_should_be_verified() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+
+    let (mp_client, unverified_collection) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let deployer_client = deployer::Client::new(&env, &env.register(deployer::WASM, ()));
+    deployer_client.initialize(&env.deployer().upload_contract_wasm(collection::WASM));
+    let verified_collection = collection::Client::new(
+        &env,
+        &deployer_client.deploy_new_collection(
+            &BytesN::from_array(&env, &[0; 32]),
+            &seller,
+            &String::from_str(&env, "Stellar Kitties"),
+            &String::from_str(&env, "STK"),
+            &true,
+        ),
+    );
+    // its admin can still swap the implementation of a collection deployed without the lock
+    let upgradable_collection = deployer_client.deploy_new_collection(
+        &BytesN::from_array(&env, &[1; 32]),
+        &seller,
+        &String::from_str(&env, "Stellar Puppers"),
+        &String::from_str(&env, "STP"),
+        &false,
+    );
+    verified_collection.create_token(&seller, &1, &Bytes::new(&env), &None, &true, &0);
+    verified_collection.mint(&seller, &seller, &1, &2);
+
+    // verification is off by default
+    assert_eq!(
+        mp_client.get_collection_verification(),
+        CollectionVerification {
+            required: false,
+            deployer: None,
+        }
+    );
+    assert!(!mp_client.is_verified_collection(&verified_collection.address));
+
+    mp_client.set_collection_verification(&true, &Some(deployer_client.address.clone()));
+    assert!(mp_client.is_verified_collection(&verified_collection.address));
+    assert!(!mp_client.is_verified_collection(&unverified_collection.address));
+    assert!(!mp_client.is_verified_collection(&upgradable_collection));
+
+    let item_info = ItemInfo {
+        collection_addr: unverified_collection.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    assert_eq!(
+        mp_client.try_create_auction(&item_info, &seller, &WEEKLY, &None, &None),
+        Err(Ok(ContractError::CollectionNotVerified))
+    );
+    assert_eq!(
+        mp_client.try_list_item(
+            &seller,
+            &unverified_collection.address,
+            &1,
+            &1,
+            &10,
+            &token_client.address
+        ),
+        Err(Ok(ContractError::CollectionNotVerified))
+    );
+
+    mp_client.create_auction(
+        &ItemInfo {
+            collection_addr: verified_collection.address.clone(),
+            ..item_info
+        },
+        &seller,
+        &WEEKLY,
+        &None,
+        &None,
+    );
+    mp_client.list_item(
+        &seller,
+        &verified_collection.address,
+        &1,
+        &1,
+        &10,
+        &token_client.address,
+    );
+}
+
+#[test]
+fn admin_should_verify_collections_manually() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &20);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    let other_collection = create_and_initialize_collection(&env, &seller, "Other", "OTH");
+
+    mp_client.set_collection_verification(&true, &None);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    assert_eq!(
+        mp_client.try_create_auction(&item_info, &seller, &WEEKLY, &None, &None),
+        Err(Ok(ContractError::CollectionNotVerified))
+    );
+
+    mp_client.verify_collection(&collection_client.address);
+    assert!(mp_client.is_verified_collection(&collection_client.address));
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    // every item of a bundle must come from a verified collection
+    assert_eq!(
+        mp_client.try_create_bundle_auction(
+            &vec![
+                &env,
+                BundleItem {
+                    collection_addr: collection_client.address.clone(),
+                    item_id: 1,
+                    amount: 1,
+                },
+                BundleItem {
+                    collection_addr: other_collection.address.clone(),
+                    item_id: 1,
+                    amount: 1,
+                },
+            ],
+            &seller,
+            &WEEKLY,
+            &None,
+            &None,
+            &None
+        ),
+        Err(Ok(ContractError::CollectionNotVerified))
+    );
+
+    mp_client.unverify_collection(&collection_client.address);
+    assert!(!mp_client.is_verified_collection(&collection_client.address));
+    assert_eq!(
+        mp_client.try_create_auction(&item_info, &seller, &WEEKLY, &None, &None),
+        Err(Ok(ContractError::CollectionNotVerified))
+    );
+
+    // without the requirement any collection can be auctioned again
+    mp_client.set_collection_verification(&false, &None);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+}
@@ -0,0 +1,316 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, Vec,
+};
+
+use crate::{
+    storage::{Auction, AuctionStatus, ItemInfo},
+    test::setup::{
+        create_and_initialize_collection, create_multiple_auctions, deploy_token_contract,
+        generate_marketplace_and_collection_client, DAY, WEEKLY,
+    },
+};
+
+fn ids(auctions: &Vec<Auction>) -> Vec<u64> {
+    let mut ids = Vec::new(auctions.env());
+    for auction in auctions.iter() {
+        ids.push_back(auction.id);
+    }
+    ids
+}
+
+#[test]
+fn auctions_should_be_found_by_collection_item_and_status() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &50);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    let other_collection = create_and_initialize_collection(&env, &seller, "Cats", "CAT");
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    let other_item_info = ItemInfo {
+        collection_addr: other_collection.address.clone(),
+        ..item_info.clone()
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.create_auction(&other_item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.create_auction(&other_item_info, &seller, &WEEKLY, &None, &None);
+
+    let page = mp_client.get_auctions_by_collection(&collection_client.address, &None, &None);
+    assert_eq!(ids(&page.auctions), vec![&env, 1, 3]);
+    assert_eq!(page.next_cursor, None);
+
+    let page = mp_client.get_auctions_by_item(&other_collection.address, &1, &None, &Some(1));
+    assert_eq!(ids(&page.auctions), vec![&env, 2]);
+    assert_eq!(page.next_cursor, Some(2));
+    let page = mp_client.get_auctions_by_item(&other_collection.address, &1, &Some(2), &Some(1));
+    assert_eq!(ids(&page.auctions), vec![&env, 4]);
+    assert_eq!(page.next_cursor, None);
+
+    assert!(mp_client
+        .get_auctions_by_item(&collection_client.address, &2, &None, &None)
+        .auctions
+        .is_empty());
+
+    let page = mp_client.get_auctions_by_seller(&seller, &None, &Some(3));
+    assert_eq!(ids(&page.auctions), vec![&env, 1, 2, 3]);
+    assert_eq!(page.next_cursor, Some(3));
+    let page = mp_client.get_auctions_by_seller(&seller, &page.next_cursor, &Some(3));
+    assert_eq!(ids(&page.auctions), vec![&env, 4]);
+    assert_eq!(page.next_cursor, None);
+
+    mp_client.pause(&2);
+    mp_client.cancel_auction(&3);
+
+    let status_ids = |status| {
+        ids(&mp_client
+            .get_auctions_by_status(&status, &None, &None)
+            .auctions)
+    };
+    assert_eq!(status_ids(AuctionStatus::Active), vec![&env, 1, 4]);
+    assert_eq!(status_ids(AuctionStatus::Paused), vec![&env, 2]);
+    assert_eq!(status_ids(AuctionStatus::Cancelled), vec![&env, 3]);
+    assert!(status_ids(AuctionStatus::Ended).is_empty());
+
+    mp_client.unpause(&2);
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+    mp_client.finalize_auction(&1);
+
+    assert_eq!(status_ids(AuctionStatus::Active), vec![&env, 2, 4]);
+    assert_eq!(status_ids(AuctionStatus::Ended), vec![&env, 1]);
+    assert!(status_ids(AuctionStatus::Paused).is_empty());
+}
+
+#[test]
+fn auctions_should_be_found_by_current_highest_bidder() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder_a = Address::generate(&env);
+    let bidder_b = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &20);
+    token_client.mint(&bidder_a, &100);
+    token_client.mint(&bidder_b, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    mp_client.place_bid(&1, &bidder_a, &10);
+    mp_client.place_bid(&2, &bidder_a, &10);
+    mp_client.place_bid(&1, &bidder_b, &20);
+
+    let bidder_ids = |bidder| {
+        ids(&mp_client
+            .get_auctions_by_highest_bidder(bidder, &None, &None)
+            .auctions)
+    };
+    assert_eq!(bidder_ids(&bidder_a), vec![&env, 2]);
+    assert_eq!(bidder_ids(&bidder_b), vec![&env, 1]);
+    assert!(bidder_ids(&Address::generate(&env)).is_empty());
+}
+
+#[test]
+fn ending_soon_should_list_active_auctions_by_end_time() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &50);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    let durations: Vec<u64> = vec![&env, WEEKLY, DAY, 3 * DAY, 2 * DAY];
+    for duration in durations.iter() {
+        let collection = create_and_initialize_collection(&env, &seller, "Cats", "CAT");
+        mp_client.create_auction(
+            &ItemInfo {
+                collection_addr: collection.address,
+                ..item_info.clone()
+            },
+            &seller,
+            &duration,
+            &None,
+            &None,
+        );
+    }
+
+    let page = mp_client.get_ending_soon_auctions(&None, &Some(3));
+    assert_eq!(ids(&page.auctions), vec![&env, 2, 4, 3]);
+    let page = mp_client.get_ending_soon_auctions(&page.next_cursor, &Some(3));
+    assert_eq!(ids(&page.auctions), vec![&env, 1]);
+    assert_eq!(page.next_cursor, None);
+
+    // paused auctions are not ending
+    mp_client.pause(&4);
+    assert_eq!(
+        ids(&mp_client.get_ending_soon_auctions(&None, &None).auctions),
+        vec![&env, 2, 3, 1]
+    );
+
+    // auctions that are over but not finalized yet are skipped
+    env.ledger().with_mut(|li| li.timestamp = DAY + 1);
+    assert_eq!(
+        ids(&mp_client.get_ending_soon_auctions(&None, &None).auctions),
+        vec![&env, 3, 1]
+    );
+}
+
+#[test]
+fn status_pages_should_not_skip_auctions_when_statuses_change() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &40);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    create_multiple_auctions(&mp_client, &seller, &collection_client, 4);
+
+    let page = mp_client.get_auctions_by_status(&AuctionStatus::Active, &None, &Some(2));
+    assert_eq!(ids(&page.auctions), vec![&env, 1, 2]);
+    assert_eq!(page.next_cursor, Some(2));
+
+    // the cursor is the last auction of the page, so it holds while the page before it changes
+    mp_client.cancel_auction(&1);
+    mp_client.cancel_auction(&2);
+
+    let page =
+        mp_client.get_auctions_by_status(&AuctionStatus::Active, &page.next_cursor, &Some(2));
+    assert_eq!(ids(&page.auctions), vec![&env, 3, 4]);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn pages_should_continue_across_index_buckets() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &700);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    create_multiple_auctions(&mp_client, &seller, &collection_client, 70);
+
+    let page = mp_client.get_auctions_by_collection(&collection_client.address, &Some(60), &None);
+    assert_eq!(
+        ids(&page.auctions),
+        vec![&env, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70]
+    );
+    assert_eq!(page.next_cursor, None);
+
+    let page =
+        mp_client.get_auctions_by_collection(&collection_client.address, &Some(62), &Some(3));
+    assert_eq!(ids(&page.auctions), vec![&env, 63, 64, 65]);
+    assert_eq!(page.next_cursor, Some(65));
+}
+
+#[test]
+fn ending_soon_should_page_over_days_without_auctions() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &20);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &DAY, &None, &None);
+    mp_client.create_auction(&item_info, &seller, &(30 * DAY), &None, &None);
+
+    // a page only looks a bounded number of days ahead, the cursor carries on from there
+    let page = mp_client.get_ending_soon_auctions(&None, &None);
+    assert_eq!(ids(&page.auctions), vec![&env, 1]);
+    assert!(page.next_cursor.is_some());
+
+    let page = mp_client.get_ending_soon_auctions(&page.next_cursor, &None);
+    assert_eq!(ids(&page.auctions), vec![&env, 2]);
+    assert_eq!(page.next_cursor, None);
+}
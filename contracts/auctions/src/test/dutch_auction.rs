@@ -0,0 +1,225 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{AuctionStatus, DutchPriceSchedule, ItemInfo, PriceDecay},
+    test::setup::{deploy_token_contract, generate_marketplace_and_collection_client, DAY},
+};
+
+#[test]
+fn dutch_auction_price_should_decay_linearly() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&buyer, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_dutch_auction(
+        &item_info,
+        &seller,
+        &DAY,
+        &None,
+        &DutchPriceSchedule {
+            start_price: 1_000,
+            end_price: 200,
+            decay: PriceDecay::Linear,
+        },
+    );
+
+    assert_eq!(mp_client.current_price(&1), 1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY / 4);
+    assert_eq!(mp_client.current_price(&1), 800);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY / 2);
+    assert_eq!(mp_client.current_price(&1), 600);
+
+    assert_eq!(
+        mp_client.try_place_bid(&1, &buyer, &700),
+        Err(Ok(ContractError::InvalidAuctionType))
+    );
+    assert_eq!(
+        mp_client.try_buy_now(&1, &buyer, &1),
+        Err(Ok(ContractError::NoBuyNowOption))
+    );
+
+    assert_eq!(mp_client.buy_dutch_auction(&1, &buyer), 600);
+
+    let auction = mp_client.get_auction(&1);
+    assert_eq!(auction.status, AuctionStatus::Ended);
+    assert_eq!(auction.highest_bid, Some(600));
+    assert_eq!(token_client.balance(&buyer), 400);
+    mp_client.withdraw_refund(&seller, &token_client.address);
+    assert_eq!(token_client.balance(&seller), 600);
+    assert_eq!(collection_client.balance_of(&buyer, &1), 1);
+
+    assert_eq!(
+        mp_client.try_buy_dutch_auction(&1, &buyer),
+        Err(Ok(ContractError::AuctionNotActive))
+    );
+}
+
+#[test]
+fn dutch_auction_price_should_decay_in_steps() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    // the price drops by 100 every 6 hours
+    mp_client.create_dutch_auction(
+        &item_info,
+        &seller,
+        &DAY,
+        &None,
+        &DutchPriceSchedule {
+            start_price: 500,
+            end_price: 100,
+            decay: PriceDecay::Stepwise(DAY / 4),
+        },
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = DAY / 4 - 1);
+    assert_eq!(mp_client.current_price(&1), 500);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY / 4);
+    assert_eq!(mp_client.current_price(&1), 400);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY - 1);
+    assert_eq!(mp_client.current_price(&1), 200);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+    assert_eq!(mp_client.current_price(&1), 100);
+
+    // unsold dutch auctions end without a sale
+    env.ledger().with_mut(|li| li.timestamp = DAY + 1);
+    assert_eq!(
+        mp_client.try_buy_dutch_auction(&1, &Address::generate(&env)),
+        Err(Ok(ContractError::AuctionNotActive))
+    );
+    mp_client.finalize_auction(&1);
+    assert_eq!(mp_client.get_auction(&1).status, AuctionStatus::Ended);
+    assert_eq!(collection_client.balance_of(&seller, &1), 2);
+}
+
+#[test]
+fn create_dutch_auction_should_validate_the_price_schedule() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+
+    assert_eq!(
+        mp_client.try_create_dutch_auction(
+            &item_info,
+            &seller,
+            &DAY,
+            &None,
+            &DutchPriceSchedule {
+                start_price: 100,
+                end_price: 100,
+                decay: PriceDecay::Linear
+            }
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+    assert_eq!(
+        mp_client.try_create_dutch_auction(
+            &item_info,
+            &seller,
+            &DAY,
+            &None,
+            &DutchPriceSchedule {
+                start_price: 500,
+                end_price: 100,
+                decay: PriceDecay::Stepwise(DAY + 1)
+            }
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+    assert_eq!(
+        mp_client.try_create_dutch_auction(
+            &ItemInfo {
+                buy_now_price: Some(300),
+                ..item_info.clone()
+            },
+            &seller,
+            &DAY,
+            &None,
+            &DutchPriceSchedule {
+                start_price: 500,
+                end_price: 100,
+                decay: PriceDecay::Linear
+            }
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    mp_client.create_auction(&item_info, &seller, &DAY, &None, &None);
+    assert_eq!(
+        mp_client.try_current_price(&1),
+        Err(Ok(ContractError::InvalidAuctionType))
+    );
+}
@@ -0,0 +1,123 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+use crate::{
+    storage::{AuctionStatus, DataKey, ItemInfo},
+    test::setup::{deploy_token_contract, generate_marketplace_and_collection_client, WEEKLY},
+};
+
+#[test]
+fn unsold_items_should_return_to_the_seller() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &20);
+    token_client.mint(&bidder, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: Some(50),
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    // both items are held by the marketplace and cannot be moved by the seller
+    assert_eq!(collection_client.balance_of(&seller, &1), 0);
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 2);
+    assert!(collection_client
+        .try_safe_transfer_from(&seller, &seller, &bidder, &1, &1)
+        .is_err());
+
+    mp_client.place_bid(&2, &bidder, &40);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+
+    // no bids
+    mp_client.finalize_auction(&1);
+    assert_eq!(collection_client.balance_of(&seller, &1), 1);
+
+    // minimum price not reached
+    mp_client.finalize_auction(&2);
+    assert_eq!(collection_client.balance_of(&seller, &1), 2);
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 0);
+    mp_client.withdraw_refund(&bidder, &token_client.address);
+    assert_eq!(token_client.balance(&bidder), 100);
+}
+
+#[test]
+fn finalize_should_refund_winner_when_non_escrowed_item_is_gone() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    // turn the auction into one created before items were escrowed
+    env.as_contract(&mp_client.address, || {
+        env.storage().persistent().remove(&DataKey::EscrowedItem(1));
+        collection_client.safe_transfer_from(
+            &mp_client.address,
+            &mp_client.address,
+            &seller,
+            &1,
+            &1,
+        );
+    });
+    collection_client.set_approval_for_transfer(&seller, &mp_client.address, &1, &true);
+
+    mp_client.place_bid(&1, &bidder, &40);
+    assert_eq!(token_client.balance(&bidder), 60);
+
+    // the seller moves the item away before the end of the auction
+    collection_client.safe_transfer_from(&seller, &seller, &other, &1, &2);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+    mp_client.finalize_auction(&1);
+
+    assert_eq!(mp_client.get_auction(&1).status, AuctionStatus::Ended);
+    mp_client.withdraw_refund(&bidder, &token_client.address);
+    assert_eq!(token_client.balance(&bidder), 100);
+    assert_eq!(token_client.balance(&seller), 0);
+    assert_eq!(collection_client.balance_of(&bidder, &1), 0);
+}
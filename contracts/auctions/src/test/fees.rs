@@ -0,0 +1,170 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+use crate::{
+    contract::{MarketplaceContract, MarketplaceContractClient},
+    error::ContractError,
+    storage::ItemInfo,
+    test::setup::{
+        deploy_token_contract, generate_marketplace_and_collection_client, withdraw_refunds, WEEKLY,
+    },
+};
+
+#[test]
+fn finalize_auction_should_accrue_protocol_fee() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let creator = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &creator,
+        &token_client.address,
+        None,
+        None,
+    );
+    // 2.5%
+    mp_client.update_config(
+        &None,
+        &Some(250),
+        &Some(treasury.clone()),
+        &None,
+        &None,
+        &None,
+    );
+
+    collection_client.mint(&creator, &seller, &1, &1);
+    // 5%
+    collection_client.set_default_royalty(&creator, &creator, &500);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    assert_eq!(mp_client.get_accrued_fees(&token_client.address), 10);
+
+    mp_client.place_bid(&1, &bidder, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+    mp_client.finalize_auction(&1);
+    withdraw_refunds(&mp_client, &token_client.address, &[&creator, &seller]);
+
+    assert_eq!(token_client.balance(&creator), 50);
+    assert_eq!(token_client.balance(&seller), 925);
+    // creation fee + protocol fee
+    assert_eq!(mp_client.get_accrued_fees(&token_client.address), 35);
+    assert_eq!(token_client.balance(&mp_client.address), 35);
+
+    mp_client.withdraw_fees(&token_client.address, &30);
+    assert_eq!(token_client.balance(&treasury), 30);
+    assert_eq!(mp_client.get_accrued_fees(&token_client.address), 5);
+
+    assert_eq!(
+        mp_client.try_withdraw_fees(&token_client.address, &6),
+        Err(Ok(ContractError::NotEnoughAccruedFees))
+    );
+
+    mp_client.withdraw_fees(&token_client.address, &5);
+    assert_eq!(token_client.balance(&treasury), 35);
+    assert_eq!(mp_client.get_accrued_fees(&token_client.address), 0);
+    assert_eq!(token_client.balance(&mp_client.address), 0);
+}
+
+#[test]
+fn buy_now_should_accrue_protocol_fee() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &100);
+    token_client.mint(&buyer, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    // 10%
+    mp_client.update_config(&None, &Some(1_000), &None, &None, &None, &None);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: Some(1_000),
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.place_bid(&1, &bidder, &100);
+    mp_client.buy_now(&1, &buyer, &1);
+    mp_client.withdraw_refund(&seller, &token_client.address);
+
+    assert_eq!(token_client.balance(&seller), 900);
+    assert_eq!(token_client.balance(&buyer), 0);
+    // the outbid bidder is refunded in full, pending refunds are not counted as fees
+    assert_eq!(
+        mp_client.pending_refund(&bidder, &token_client.address),
+        100
+    );
+    assert_eq!(mp_client.get_accrued_fees(&token_client.address), 110);
+    assert_eq!(token_client.balance(&mp_client.address), 210);
+
+    mp_client.withdraw_refund(&bidder, &token_client.address);
+    assert_eq!(token_client.balance(&bidder), 100);
+    assert_eq!(token_client.balance(&mp_client.address), 110);
+}
+
+#[test]
+fn protocol_fee_and_royalty_cap_cannot_exceed_the_sale_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let token_client = deploy_token_contract(&env, &admin);
+
+    let mp_client = MarketplaceContractClient::new(&env, &env.register(MarketplaceContract, ()));
+    assert_eq!(
+        mp_client.try_initialize(&admin, &token_client.address, &10, &5_000, &5_001, &admin),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    mp_client.initialize(&admin, &token_client.address, &10, &5_000, &5_000, &admin);
+
+    assert_eq!(
+        mp_client.try_update_config(&Some(5_001), &None, &None, &None, &None, &None),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+    assert_eq!(
+        mp_client.try_update_config(&None, &Some(u32::MAX), &None, &None, &None, &None),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    let config = mp_client.update_config(&Some(1_000), &Some(9_000), &None, &None, &None, &None);
+    assert_eq!(config.max_royalty_bps, 1_000);
+    assert_eq!(config.protocol_fee_bps, 9_000);
+    assert_eq!(mp_client.get_config(), config);
+}
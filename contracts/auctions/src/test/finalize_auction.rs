@@ -1,6 +1,6 @@
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    vec, Address, Env,
+    vec, Address, Bytes, Env,
 };
 
 use crate::{
@@ -15,7 +15,7 @@ use crate::{
 fn finalize_auction() {
     let env = Env::default();
     env.mock_all_auths_allowing_non_root_auth();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -48,8 +48,8 @@ fn finalize_auction() {
         amount: 1,
     };
 
-    collections_client.set_approval_for_transfer(&mp_client.address, &1, &true);
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    collections_client.set_approval_for_transfer(&seller, &mp_client.address, &1, &true);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     // 4 hours after the start of the auctions `bidder_a` places a bid
     env.ledger().with_mut(|li| li.timestamp = FOUR_HOURS);
@@ -62,6 +62,7 @@ fn finalize_auction() {
         li.timestamp = FOUR_HOURS * 2;
     });
     mp_client.place_bid(&1, &bidder_b, &10);
+    mp_client.withdraw_refund(&bidder_a, &token_client.address);
     assert_eq!(token_client.balance(&bidder_a), 100);
     assert_eq!(token_client.balance(&bidder_b), 90);
     assert_eq!(token_client.balance(&mp_client.address), 20);
@@ -71,6 +72,7 @@ fn finalize_auction() {
         li.timestamp = FOUR_HOURS * 3;
     });
     mp_client.place_bid(&1, &bidder_c, &50);
+    mp_client.withdraw_refund(&bidder_b, &token_client.address);
     assert_eq!(token_client.balance(&bidder_a), 100);
     assert_eq!(token_client.balance(&bidder_b), 100);
     assert_eq!(token_client.balance(&bidder_c), 50);
@@ -91,6 +93,7 @@ fn finalize_auction() {
         li.timestamp = FOUR_HOURS * 4;
     });
     let _ = mp_client.try_place_bid(&1, &bidder_a, &75);
+    mp_client.withdraw_refund(&bidder_c, &token_client.address);
     assert_eq!(token_client.balance(&bidder_a), 25);
     assert_eq!(token_client.balance(&bidder_b), 100);
     assert_eq!(token_client.balance(&bidder_c), 100);
@@ -99,6 +102,7 @@ fn finalize_auction() {
     // we wrap it up and the winner is `bidder_a` with a highest bid of 75
     env.ledger().with_mut(|li| li.timestamp = WEEKLY + DAY);
     mp_client.finalize_auction(&1);
+    mp_client.withdraw_refund(&seller, &token_client.address);
 
     // check if the finances are the same
     assert_eq!(token_client.balance(&bidder_a), 25);
@@ -118,7 +122,7 @@ fn finalize_auction() {
 fn fail_to_finalyze_auction_when_endtime_not_reached() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
     let seller = Address::generate(&env);
     let bidder = Address::generate(&env);
 
@@ -143,7 +147,7 @@ fn fail_to_finalyze_auction_when_endtime_not_reached() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     mp_client.place_bid(&1, &bidder, &50);
 
@@ -165,7 +169,7 @@ fn fail_to_finalyze_auction_when_endtime_not_reached() {
 fn finalize_auction_when_minimal_price_not_reached_should_refund_last_bidder() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let seller = Address::generate(&env);
     let bidder_a = Address::generate(&env);
@@ -190,7 +194,7 @@ fn finalize_auction_when_minimal_price_not_reached_should_refund_last_bidder() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     // we got the highest bid on day #1
     env.ledger().with_mut(|li| li.timestamp = DAY);
@@ -216,6 +220,11 @@ fn finalize_auction_when_minimal_price_not_reached_should_refund_last_bidder() {
             auction_token: token_client.address.clone()
         }
     );
+    assert_eq!(
+        mp_client.pending_refund(&bidder_a, &token_client.address),
+        5
+    );
+    mp_client.withdraw_refund(&bidder_a, &token_client.address);
     assert_eq!(token_client.balance(&mp_client.address), 10i128);
     assert_eq!(token_client.balance(&bidder_a), 5i128);
 }
@@ -224,7 +233,7 @@ fn finalize_auction_when_minimal_price_not_reached_should_refund_last_bidder() {
 fn fail_to_finalyze_auction_when_not_correct_state() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
     let seller = Address::generate(&env);
 
     let token_client = deploy_token_contract(&env, &Address::generate(&env));
@@ -246,18 +255,78 @@ fn fail_to_finalyze_auction_when_not_correct_state() {
         amount: 1,
     };
 
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     env.ledger().with_mut(|li| li.timestamp = DAY);
 
     mp_client.pause(&1);
 
+    assert_eq!(
+        mp_client.try_finalize_auction(&1,),
+        Err(Ok(ContractError::AuctionNotFinished))
+    );
+
+    mp_client.cancel_auction(&1);
+
     assert_eq!(
         mp_client.try_finalize_auction(&1,),
         Err(Ok(ContractError::AuctionNotActive))
     );
 }
 
+#[test]
+fn paused_auction_should_be_finalized_once_it_is_over() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &50);
+
+    let (mp_client, nft_collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: nft_collection_client.address.clone(),
+        item_id: 1u64,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.place_bid(&1, &bidder, &20);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+    mp_client.pause(&1);
+
+    // the auction ran out while paused, so it can neither be resumed nor cancelled
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+    assert_eq!(
+        mp_client.try_unpause(&1),
+        Err(Ok(ContractError::AuctionNotActive))
+    );
+    assert_eq!(
+        mp_client.try_cancel_auction(&1),
+        Err(Ok(ContractError::AuctionCannotBeCancelled))
+    );
+
+    mp_client.finalize_auction(&1);
+
+    assert_eq!(mp_client.get_auction(&1).status, AuctionStatus::Ended);
+    assert_eq!(nft_collection_client.balance_of(&bidder, &1), 1);
+    assert_eq!(token_client.balance(&bidder), 30);
+}
+
 #[test]
 fn get_active_auctions_should_list_correct_number_of_active_auctions() {
     let env = Env::default();
@@ -276,6 +345,8 @@ fn get_active_auctions_should_list_correct_number_of_active_auctions() {
         None,
     );
 
+    nft_collection_client.create_token(&seller, &2, &Bytes::new(&env), &None, &true, &0);
+    nft_collection_client.create_token(&seller, &3, &Bytes::new(&env), &None, &true, &0);
     nft_collection_client.mint_batch(
         &seller,
         &seller,
@@ -306,9 +377,9 @@ fn get_active_auctions_should_list_correct_number_of_active_auctions() {
         buy_now_price: Some(50),
         amount: 15,
     };
-    mp_client.create_auction(&first_item, &seller, &WEEKLY);
-    mp_client.create_auction(&second_item, &seller, &WEEKLY);
-    mp_client.create_auction(&third_item, &seller, &WEEKLY);
+    mp_client.create_auction(&first_item, &seller, &WEEKLY, &None, &None);
+    mp_client.create_auction(&second_item, &seller, &WEEKLY, &None, &None);
+    mp_client.create_auction(&third_item, &seller, &WEEKLY, &None, &None);
 
     assert_eq!(mp_client.get_active_auctions(&None, &None).len(), 3);
 
@@ -15,16 +15,15 @@ use super::setup::deploy_token_contract;
 fn initialize_and_update_admin_should_work() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let new_admin = Address::generate(&env);
 
     let token_client = deploy_token_contract(&env, &admin);
-    let mp_client =
-        MarketplaceContractClient::new(&env, &env.register_contract(None, MarketplaceContract {}));
+    let mp_client = MarketplaceContractClient::new(&env, &env.register(MarketplaceContract, ()));
 
-    mp_client.initialize(&admin, &token_client.address, &10);
+    mp_client.initialize(&admin, &token_client.address, &10, &1_000, &0, &admin);
     mp_client.update_admin(&new_admin);
 }
 
@@ -32,7 +31,7 @@ fn initialize_and_update_admin_should_work() {
 fn mp_should_create_auction() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
     let seller = Address::generate(&env);
 
     let token_client = deploy_token_contract(&env, &Address::generate(&env));
@@ -56,7 +55,7 @@ fn mp_should_create_auction() {
 
     // check if we have minted two
     assert_eq!(nft_collection_client.balance_of(&seller, &1), 2);
-    mp_client.create_auction(&item_info, &seller, &WEEKLY);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
 
     assert_eq!(
         mp_client.get_auction(&1),
@@ -76,7 +75,7 @@ fn mp_should_create_auction() {
 fn initialize_twice_should_fail() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let admin = Address::generate(&env);
     let seller = Address::generate(&env);
@@ -93,7 +92,7 @@ fn initialize_twice_should_fail() {
     );
 
     assert_eq!(
-        mp_client.try_initialize(&admin, &token_client.address, &10),
+        mp_client.try_initialize(&admin, &token_client.address, &10, &1_000, &0, &admin),
         Err(Ok(ContractError::AlreadyInitialized))
     );
 }
@@ -102,7 +101,7 @@ fn initialize_twice_should_fail() {
 fn mp_should_fail_to_create_auction_where_not_enought_balance_of_the_item() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
     let seller = Address::generate(&env);
 
     let token_client = deploy_token_contract(&env, &Address::generate(&env));
@@ -118,7 +117,7 @@ fn mp_should_fail_to_create_auction_where_not_enought_balance_of_the_item() {
         None,
     );
 
-    let collection_addr = env.register_contract_wasm(None, collection::WASM);
+    let collection_addr = env.register(collection::WASM, ());
 
     let collection_client = collection::Client::new(&env, &collection_addr);
     collection_client.initialize(
@@ -136,7 +135,7 @@ fn mp_should_fail_to_create_auction_where_not_enought_balance_of_the_item() {
     };
 
     assert_eq!(
-        mp_client.try_create_auction(&item_info, &seller, &WEEKLY),
+        mp_client.try_create_auction(&item_info, &seller, &WEEKLY, &None, &None),
         Err(Ok(ContractError::NotEnoughBalance))
     );
 }
@@ -145,7 +144,7 @@ fn mp_should_fail_to_create_auction_where_not_enought_balance_of_the_item() {
 fn mp_should_be_able_create_multiple_auctions_and_query_them_with_pagination() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
 
     let seller = Address::generate(&env);
     let token_client = deploy_token_contract(&env, &Address::generate(&env));
@@ -159,6 +158,9 @@ fn mp_should_be_able_create_multiple_auctions_and_query_them_with_pagination() {
         None,
     );
 
+    // nothing to list before the first auction
+    assert!(mp_client.get_active_auctions(&None, &None).is_empty());
+
     create_multiple_auctions(&mp_client, &seller, &collection_client, 25);
 
     //we have created 25 auctions and if we don't specify anything the default search would be
@@ -183,7 +185,7 @@ fn mp_should_be_able_create_multiple_auctions_and_query_them_with_pagination() {
     );
 
     // manaul from 10..=20
-    let result = mp_client.get_active_auctions(&Some(10), &Some(20));
+    let result = mp_client.get_active_auctions(&Some(10), &Some(11));
     assert_eq!(
         result
             .into_iter()
@@ -208,7 +210,7 @@ fn mp_should_be_able_create_multiple_auctions_and_query_them_with_pagination() {
 fn get_auction_by_id_should_return_an_err_when_id_not_found() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
     let seller = Address::generate(&env);
 
     let token_client = deploy_token_contract(&env, &Address::generate(&env));
@@ -227,10 +229,10 @@ fn get_auction_by_id_should_return_an_err_when_id_not_found() {
 }
 
 #[test]
-fn get_auction_by_seller_should_return_an_err_when_id_not_found() {
+fn get_auction_by_seller_should_return_an_empty_list_when_seller_has_no_auctions() {
     let env = Env::default();
     env.mock_all_auths();
-    env.budget().reset_unlimited();
+    env.cost_estimate().budget().reset_unlimited();
     let seller = Address::generate(&env);
 
     let token_client = deploy_token_contract(&env, &Address::generate(&env));
@@ -242,10 +244,10 @@ fn get_auction_by_seller_should_return_an_err_when_id_not_found() {
         None,
     );
 
-    assert_eq!(
-        mp_client.try_get_auctions_by_seller(&Address::generate(&env)),
-        Err(Ok(ContractError::AuctionNotFound))
-    )
+    assert!(mp_client
+        .get_auctions_by_seller(&Address::generate(&env), &None, &None)
+        .auctions
+        .is_empty());
 }
 
 #[test]
@@ -267,10 +269,11 @@ fn should_fail_to_create_auction_when_seller_cannot_cover_the_fees() {
         item_id: 1,
         minimum_price: None,
         buy_now_price: None,
+        amount: 1,
     };
 
     assert_eq!(
-        mp_client.try_create_auction(&item_info, &seller, &WEEKLY),
+        mp_client.try_create_auction(&item_info, &seller, &WEEKLY, &None, &None),
         Err(Ok(ContractError::AuctionCreationFeeNotCovered))
     );
 }
@@ -300,8 +303,7 @@ fn mp_should_not_create_auction_with_item_info_with_zero_amount() {
     };
 
     assert_eq!(
-        mp_client.try_create_auction(&item_info, &seller, &WEEKLY),
+        mp_client.try_create_auction(&item_info, &seller, &WEEKLY, &None, &None),
         Err(Ok(ContractError::InvalidInputs))
     );
 }
-
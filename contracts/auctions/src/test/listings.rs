@@ -0,0 +1,248 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Bytes, Env};
+
+use crate::{
+    error::ContractError,
+    storage::{ItemInfo, ListingStatus},
+    test::setup::{deploy_token_contract, generate_marketplace_and_collection_client, WEEKLY},
+};
+
+#[test]
+fn buy_listing_should_sell_items_for_a_fixed_price() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let auction_token = deploy_token_contract(&env, &Address::generate(&env));
+    let payment_token = deploy_token_contract(&env, &Address::generate(&env));
+    payment_token.mint(&buyer, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &auction_token.address,
+        None,
+        None,
+    );
+
+    // no creation fee is charged for listings
+    let listing = mp_client.list_item(
+        &seller,
+        &collection_client.address,
+        &1,
+        &2,
+        &100,
+        &payment_token.address,
+    );
+    assert_eq!(listing.id, 1);
+    assert_eq!(listing.status, ListingStatus::Active);
+    assert!(collection_client.is_approved_for_transfer(&seller, &mp_client.address, &1));
+
+    assert_eq!(
+        mp_client.try_buy_listing(&1, &buyer, &3),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+    assert_eq!(
+        mp_client.try_buy_listing(&1, &seller, &1),
+        Err(Ok(ContractError::InvalidBidder))
+    );
+
+    let listing = mp_client.buy_listing(&1, &buyer, &1);
+    assert_eq!(listing.amount, 1);
+    assert_eq!(listing.status, ListingStatus::Active);
+    assert_eq!(collection_client.balance_of(&buyer, &1), 1);
+    assert_eq!(payment_token.balance(&buyer), 900);
+    mp_client.withdraw_refund(&seller, &payment_token.address);
+    assert_eq!(payment_token.balance(&seller), 100);
+
+    let listing = mp_client.buy_listing(&1, &buyer, &1);
+    assert_eq!(listing.amount, 0);
+    assert_eq!(listing.status, ListingStatus::Sold);
+    assert_eq!(collection_client.balance_of(&buyer, &1), 2);
+    assert_eq!(collection_client.balance_of(&seller, &1), 0);
+    mp_client.withdraw_refund(&seller, &payment_token.address);
+    assert_eq!(payment_token.balance(&seller), 200);
+    assert!(!collection_client.is_approved_for_transfer(&seller, &mp_client.address, &1));
+
+    assert_eq!(
+        mp_client.try_buy_listing(&1, &buyer, &1),
+        Err(Ok(ContractError::ListingNotActive))
+    );
+}
+
+#[test]
+fn seller_should_update_and_delist_listings() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&buyer, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    mp_client.list_item(
+        &seller,
+        &collection_client.address,
+        &1,
+        &1,
+        &100,
+        &token_client.address,
+    );
+
+    assert_eq!(
+        mp_client.try_update_listing(&1, &Some(3), &None),
+        Err(Ok(ContractError::NotEnoughBalance))
+    );
+    let listing = mp_client.update_listing(&1, &Some(2), &Some(250));
+    assert_eq!(listing.amount, 2);
+    assert_eq!(listing.price, 250);
+
+    // the same item is auctioned as well, escrowed auctions do not need the approval
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+
+    mp_client.delist(&1);
+    assert_eq!(mp_client.get_listing(&1).status, ListingStatus::Delisted);
+    assert!(!collection_client.is_approved_for_transfer(&seller, &mp_client.address, &1));
+
+    assert_eq!(
+        mp_client.try_buy_listing(&1, &buyer, &1),
+        Err(Ok(ContractError::ListingNotActive))
+    );
+    assert_eq!(
+        mp_client.try_delist(&1),
+        Err(Ok(ContractError::ListingNotActive))
+    );
+    assert_eq!(
+        mp_client.try_update_listing(&1, &None, &Some(1)),
+        Err(Ok(ContractError::ListingNotActive))
+    );
+
+    mp_client.cancel_auction(&1);
+    assert_eq!(collection_client.balance_of(&seller, &1), 2);
+}
+
+#[test]
+fn listings_should_be_queryable() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let other_seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    for id in 2..=4 {
+        collection_client.create_token(&seller, &id, &Bytes::new(&env), &None, &true, &0);
+        collection_client.mint(&seller, &seller, &id, &1);
+    }
+    collection_client.mint(&seller, &other_seller, &2, &1);
+
+    assert_eq!(
+        mp_client.try_list_item(
+            &seller,
+            &collection_client.address,
+            &5,
+            &1,
+            &100,
+            &token_client.address
+        ),
+        Err(Ok(ContractError::NotEnoughBalance))
+    );
+
+    let first = mp_client.list_item(
+        &seller,
+        &collection_client.address,
+        &1,
+        &1,
+        &100,
+        &token_client.address,
+    );
+    let second = mp_client.list_item(
+        &seller,
+        &collection_client.address,
+        &2,
+        &1,
+        &200,
+        &token_client.address,
+    );
+    let third = mp_client.list_item(
+        &other_seller,
+        &collection_client.address,
+        &2,
+        &1,
+        &300,
+        &token_client.address,
+    );
+    let fourth = mp_client.list_item(
+        &seller,
+        &collection_client.address,
+        &3,
+        &1,
+        &400,
+        &token_client.address,
+    );
+    mp_client.delist(&second.id);
+
+    assert_eq!(
+        mp_client.get_active_listings(&None, &None),
+        vec![&env, first.clone(), third.clone(), fourth.clone()]
+    );
+    assert_eq!(
+        mp_client.get_active_listings(&Some(2), &Some(2)),
+        vec![&env, third.clone()]
+    );
+    assert_eq!(
+        mp_client.get_listings_by_seller(&seller, &None, &None),
+        vec![
+            &env,
+            first.clone(),
+            mp_client.get_listing(&second.id),
+            fourth.clone()
+        ]
+    );
+    assert_eq!(
+        mp_client.get_listings_by_seller(&seller, &Some(1), &Some(1)),
+        vec![&env, mp_client.get_listing(&second.id)]
+    );
+    assert_eq!(
+        mp_client.get_listings_by_seller(&seller, &Some(3), &None),
+        vec![&env]
+    );
+    assert_eq!(
+        mp_client.get_listings_by_seller(&other_seller, &None, &None),
+        vec![&env, third]
+    );
+    assert_eq!(
+        mp_client.try_get_listing(&5),
+        Err(Ok(ContractError::ListingNotFound))
+    );
+}
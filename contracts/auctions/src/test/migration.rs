@@ -0,0 +1,220 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+use crate::{
+    error::ContractError,
+    storage::{AcceptedToken, Auction, AuctionStatus, DataKey, HighestBid, ItemInfo, LegacyConfig},
+    test::setup::{deploy_token_contract, generate_marketplace_and_collection_client, WEEKLY},
+};
+
+#[test]
+fn migrate_auctions_should_move_instance_entries_to_persistent_storage() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let legacy_auction = |id| Auction {
+        id,
+        item_info: ItemInfo {
+            collection_addr: collection_client.address.clone(),
+            item_id: 1,
+            minimum_price: None,
+            buy_now_price: None,
+            amount: 1,
+        },
+        seller: seller.clone(),
+        highest_bid: None,
+        end_time: WEEKLY,
+        status: AuctionStatus::Active,
+        auction_token: token_client.address.clone(),
+    };
+    // the layout used before auctions got their own persistent entries
+    env.as_contract(&mp_client.address, || {
+        let storage = env.storage().instance();
+        storage.set(&1u64, &legacy_auction(1));
+        storage.set(&2u64, &legacy_auction(2));
+        storage.set(&seller, &vec![&env, legacy_auction(1), legacy_auction(2)]);
+        storage.set(
+            &DataKey::HighestBid(1),
+            &HighestBid {
+                bid: 40,
+                bidder: bidder.clone(),
+            },
+        );
+        storage.set(&DataKey::AuctionId, &2u64);
+    });
+
+    assert!(mp_client.try_get_auction(&1).is_err());
+
+    // the bidder is already active on an auction created after the upgrade
+    let item_info = legacy_auction(3).item_info;
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.place_bid(&3, &bidder, &10);
+
+    assert_eq!(mp_client.migrate_auctions(&None, &Some(1)), 1);
+    assert_eq!(mp_client.migrate_auctions(&Some(2), &None), 1);
+    // migrating again has no effect
+    assert_eq!(mp_client.migrate_auctions(&None, &None), 0);
+
+    assert_eq!(mp_client.get_auction(&1), legacy_auction(1));
+    assert_eq!(mp_client.get_auction(&2), legacy_auction(2));
+    assert_eq!(
+        mp_client
+            .get_auctions_by_seller(&seller, &None, &None)
+            .auctions
+            .len(),
+        3
+    );
+    assert_eq!(mp_client.get_highest_bid(&1).bid, 40);
+
+    let bidder_auctions = mp_client.get_auctions_by_highest_bidder(&bidder, &None, &None);
+    assert_eq!(bidder_auctions.auctions.len(), 2);
+    assert_eq!(bidder_auctions.auctions.get_unchecked(0).id, 1);
+    assert_eq!(bidder_auctions.auctions.get_unchecked(1).id, 3);
+
+    env.as_contract(&mp_client.address, || {
+        let storage = env.storage().instance();
+        assert!(!storage.has(&1u64));
+        assert!(!storage.has(&2u64));
+        assert!(!storage.has(&seller));
+        assert!(!storage.has(&DataKey::HighestBid(1)));
+    });
+}
+
+#[test]
+fn migrate_config_should_convert_the_legacy_config() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&admin, &20);
+
+    let (mp_client, collection_client) =
+        generate_marketplace_and_collection_client(&env, &admin, &token_client.address, None, None);
+
+    // the layout used before royalties, protocol fees and accepted tokens
+    env.as_contract(&mp_client.address, || {
+        let storage = env.storage().persistent();
+        storage.set(
+            &DataKey::Config,
+            &LegacyConfig {
+                auction_token: token_client.address.clone(),
+                auction_creation_fee: 20,
+            },
+        );
+        storage.remove(&DataKey::AcceptedTokens);
+    });
+
+    assert!(mp_client.try_get_config().is_err());
+
+    let config = mp_client.migrate_config(&500, &100, &treasury);
+    assert_eq!(mp_client.get_config(), config);
+    assert_eq!(config.auction_token, token_client.address);
+    assert_eq!(config.max_royalty_bps, 500);
+    assert_eq!(config.protocol_fee_bps, 100);
+    assert_eq!(config.treasury, treasury);
+    assert_eq!(
+        mp_client.get_accepted_tokens(),
+        vec![
+            &env,
+            AcceptedToken {
+                token: token_client.address.clone(),
+                creation_fee: 20,
+                min_price: 0,
+            }
+        ]
+    );
+
+    assert_eq!(
+        mp_client.try_migrate_config(&500, &100, &treasury),
+        Err(Ok(ContractError::NothingToMigrate))
+    );
+
+    // auctions are created again, charging the legacy creation fee
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &admin, &WEEKLY, &None, &None);
+    assert_eq!(token_client.balance(&admin), 0);
+}
+
+#[test]
+fn migrated_auctions_should_keep_the_approval_of_their_item_while_live() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    // auctions created before items were escrowed left the item with the seller
+    collection_client.set_approval_for_transfer(&seller, &mp_client.address, &1, &true);
+    env.as_contract(&mp_client.address, || {
+        let storage = env.storage().instance();
+        storage.set(
+            &1u64,
+            &Auction {
+                id: 1,
+                item_info: ItemInfo {
+                    collection_addr: collection_client.address.clone(),
+                    item_id: 1,
+                    minimum_price: None,
+                    buy_now_price: None,
+                    amount: 1,
+                },
+                seller: seller.clone(),
+                highest_bid: None,
+                end_time: WEEKLY,
+                status: AuctionStatus::Active,
+                auction_token: token_client.address.clone(),
+            },
+        );
+        storage.set(&DataKey::AuctionId, &1u64);
+    });
+    mp_client.migrate_auctions(&None, &None);
+
+    mp_client.list_item(
+        &seller,
+        &collection_client.address,
+        &1,
+        &1,
+        &100,
+        &token_client.address,
+    );
+    mp_client.delist(&1);
+    assert!(collection_client.is_approved_for_transfer(&seller, &mp_client.address, &1));
+
+    mp_client.cancel_auction(&1);
+    assert!(!collection_client.is_approved_for_transfer(&seller, &mp_client.address, &1));
+}
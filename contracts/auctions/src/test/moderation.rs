@@ -0,0 +1,274 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{AuctionStatus, ItemInfo, OfferItem},
+    test::setup::{
+        create_and_initialize_collection, deploy_token_contract,
+        generate_marketplace_and_collection_client, WEEKLY,
+    },
+};
+
+#[test]
+fn paused_marketplace_should_halt_trading_but_allow_refunds() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder_a = Address::generate(&env);
+    let bidder_b = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder_a, &100);
+    token_client.mint(&bidder_b, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: Some(80),
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.place_bid(&1, &bidder_a, &10);
+    mp_client.place_bid(&1, &bidder_b, &20);
+
+    mp_client.pause_marketplace();
+    assert!(mp_client.is_marketplace_paused());
+
+    assert_eq!(
+        mp_client.try_create_auction(
+            &ItemInfo {
+                item_id: 2,
+                ..item_info.clone()
+            },
+            &seller,
+            &WEEKLY,
+            &None,
+            &None
+        ),
+        Err(Ok(ContractError::MarketplacePaused))
+    );
+    assert_eq!(
+        mp_client.try_place_bid(&1, &bidder_a, &30),
+        Err(Ok(ContractError::MarketplacePaused))
+    );
+    assert_eq!(
+        mp_client.try_buy_now(&1, &bidder_a, &1),
+        Err(Ok(ContractError::MarketplacePaused))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+    assert_eq!(
+        mp_client.try_finalize_auction(&1),
+        Err(Ok(ContractError::MarketplacePaused))
+    );
+
+    // outbid bidders can still get their funds back
+    assert_eq!(
+        mp_client.withdraw_refund(&bidder_a, &token_client.address),
+        10
+    );
+    assert_eq!(token_client.balance(&bidder_a), 100);
+
+    mp_client.unpause_marketplace();
+    assert!(!mp_client.is_marketplace_paused());
+
+    mp_client.finalize_auction(&1);
+    assert_eq!(mp_client.get_auction(&1).status, AuctionStatus::Ended);
+    assert_eq!(collection_client.balance_of(&bidder_b, &1), 1);
+}
+
+#[test]
+fn admin_should_delist_auction_and_refund_highest_bidder() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.place_bid(&1, &bidder, &40);
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 1);
+
+    // moderation stays available while the marketplace is paused
+    mp_client.pause_marketplace();
+    mp_client.delist_auction(&1);
+
+    assert_eq!(mp_client.get_auction(&1).status, AuctionStatus::Cancelled);
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 0);
+    assert_eq!(collection_client.balance_of(&seller, &1), 2);
+    assert_eq!(mp_client.pending_refund(&bidder, &token_client.address), 40);
+
+    assert_eq!(
+        mp_client.try_delist_auction(&1),
+        Err(Ok(ContractError::AuctionNotActive))
+    );
+}
+
+#[test]
+fn blocked_sellers_and_collections_should_not_create_auctions() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let other_seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&other_seller, &10);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    let other_collection = create_and_initialize_collection(&env, &other_seller, "Other", "OTH");
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    let other_item_info = ItemInfo {
+        collection_addr: other_collection.address.clone(),
+        ..item_info.clone()
+    };
+
+    mp_client.block_seller(&seller);
+    assert!(mp_client.is_seller_blocked(&seller));
+    assert_eq!(
+        mp_client.try_create_auction(&item_info, &seller, &WEEKLY, &None, &None),
+        Err(Ok(ContractError::SellerBlocked))
+    );
+
+    mp_client.block_collection(&other_collection.address);
+    assert!(mp_client.is_collection_blocked(&other_collection.address));
+    assert_eq!(
+        mp_client.try_create_auction(&other_item_info, &other_seller, &WEEKLY, &None, &None),
+        Err(Ok(ContractError::CollectionBlocked))
+    );
+
+    mp_client.unblock_seller(&seller);
+    mp_client.unblock_collection(&other_collection.address);
+    assert!(!mp_client.is_seller_blocked(&seller));
+    assert!(!mp_client.is_collection_blocked(&other_collection.address));
+
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.create_auction(&other_item_info, &other_seller, &WEEKLY, &None, &None);
+}
+
+#[test]
+fn blocked_sellers_and_collections_should_not_trade_through_listings_and_offers() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&buyer, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let listing = mp_client.list_item(
+        &seller,
+        &collection_client.address,
+        &1,
+        &1,
+        &100,
+        &token_client.address,
+    );
+    let offer = mp_client.make_offer(
+        &buyer,
+        &OfferItem {
+            collection_addr: collection_client.address.clone(),
+            item_id: Some(1),
+            amount: 1,
+        },
+        &50,
+        &token_client.address,
+        &WEEKLY,
+    );
+
+    mp_client.block_seller(&seller);
+    assert_eq!(
+        mp_client.try_list_item(
+            &seller,
+            &collection_client.address,
+            &1,
+            &1,
+            &100,
+            &token_client.address
+        ),
+        Err(Ok(ContractError::SellerBlocked))
+    );
+    assert_eq!(
+        mp_client.try_buy_listing(&listing.id, &buyer, &1),
+        Err(Ok(ContractError::SellerBlocked))
+    );
+    assert_eq!(
+        mp_client.try_accept_offer(&offer.id, &seller, &1),
+        Err(Ok(ContractError::SellerBlocked))
+    );
+
+    mp_client.unblock_seller(&seller);
+    mp_client.block_collection(&collection_client.address);
+    assert_eq!(
+        mp_client.try_buy_listing(&listing.id, &buyer, &1),
+        Err(Ok(ContractError::CollectionBlocked))
+    );
+    assert_eq!(
+        mp_client.try_accept_offer(&offer.id, &seller, &1),
+        Err(Ok(ContractError::CollectionBlocked))
+    );
+
+    mp_client.unblock_collection(&collection_client.address);
+    mp_client.accept_offer(&offer.id, &seller, &1);
+    assert_eq!(collection_client.balance_of(&buyer, &1), 1);
+}
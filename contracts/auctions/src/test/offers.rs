@@ -0,0 +1,192 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Bytes, Env,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{OfferItem, OfferStatus},
+    test::setup::{deploy_token_contract, generate_marketplace_and_collection_client, DAY},
+};
+
+#[test]
+fn holder_should_accept_offer_for_an_item() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let holder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&buyer, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &holder,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let offer = mp_client.make_offer(
+        &buyer,
+        &OfferItem {
+            collection_addr: collection_client.address.clone(),
+            item_id: Some(1),
+            amount: 2,
+        },
+        &300,
+        &token_client.address,
+        &DAY,
+    );
+    assert_eq!(offer.status, OfferStatus::Active);
+    assert_eq!(token_client.balance(&buyer), 700);
+    assert_eq!(token_client.balance(&mp_client.address), 300);
+
+    assert_eq!(
+        mp_client.try_accept_offer(&offer.id, &holder, &2),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+    assert_eq!(
+        mp_client.try_accept_offer(&offer.id, &buyer, &1),
+        Err(Ok(ContractError::InvalidBidder))
+    );
+
+    mp_client.accept_offer(&offer.id, &holder, &1);
+    mp_client.withdraw_refund(&holder, &token_client.address);
+
+    assert_eq!(mp_client.get_offer(&offer.id).status, OfferStatus::Accepted);
+    assert_eq!(collection_client.balance_of(&buyer, &1), 2);
+    assert_eq!(collection_client.balance_of(&holder, &1), 0);
+    assert_eq!(token_client.balance(&holder), 300);
+    assert_eq!(token_client.balance(&mp_client.address), 0);
+    assert!(!collection_client.is_approved_for_transfer(&holder, &mp_client.address, &1));
+
+    assert_eq!(
+        mp_client.try_accept_offer(&offer.id, &holder, &1),
+        Err(Ok(ContractError::OfferNotActive))
+    );
+    assert_eq!(
+        mp_client.try_cancel_offer(&offer.id),
+        Err(Ok(ContractError::OfferNotActive))
+    );
+}
+
+#[test]
+fn collection_wide_offer_should_accept_any_item() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&buyer, &1_000);
+
+    let (mp_client, collection_client) =
+        generate_marketplace_and_collection_client(&env, &admin, &token_client.address, None, None);
+    collection_client.create_token(&admin, &7, &Bytes::new(&env), &None, &true, &0);
+    collection_client.mint(&admin, &holder, &7, &1);
+
+    mp_client.make_offer(
+        &buyer,
+        &OfferItem {
+            collection_addr: collection_client.address.clone(),
+            item_id: None,
+            amount: 1,
+        },
+        &150,
+        &token_client.address,
+        &DAY,
+    );
+
+    assert_eq!(
+        mp_client.try_accept_offer(&1, &holder, &1),
+        Err(Ok(ContractError::NotEnoughBalance))
+    );
+
+    mp_client.accept_offer(&1, &holder, &7);
+    mp_client.withdraw_refund(&holder, &token_client.address);
+
+    assert_eq!(collection_client.balance_of(&buyer, &7), 1);
+    assert_eq!(collection_client.balance_of(&holder, &7), 0);
+    assert_eq!(token_client.balance(&holder), 150);
+}
+
+#[test]
+fn buyer_should_reclaim_cancelled_and_expired_offers() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let holder = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&buyer, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &holder,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item = OfferItem {
+        collection_addr: collection_client.address.clone(),
+        item_id: Some(1),
+        amount: 1,
+    };
+
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+    assert_eq!(
+        mp_client.try_make_offer(&buyer, &item, &100, &token_client.address, &DAY),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    let first = mp_client.make_offer(&buyer, &item, &100, &token_client.address, &(2 * DAY));
+    let second = mp_client.make_offer(&buyer, &item, &200, &token_client.address, &(3 * DAY));
+    assert_eq!(token_client.balance(&buyer), 700);
+
+    mp_client.cancel_offer(&first.id);
+    assert_eq!(token_client.balance(&buyer), 800);
+    assert_eq!(
+        mp_client.get_active_offers_by_collection(&collection_client.address, &None, &None),
+        vec![&env, second.clone()]
+    );
+    assert_eq!(
+        mp_client.get_active_offers_by_collection(&collection_client.address, &None, &Some(1)),
+        vec![&env]
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 3 * DAY + 1);
+    assert_eq!(
+        mp_client.try_accept_offer(&second.id, &holder, &1),
+        Err(Ok(ContractError::OfferExpired))
+    );
+    assert_eq!(
+        mp_client.get_active_offers_by_collection(&collection_client.address, &None, &None),
+        vec![&env]
+    );
+
+    mp_client.cancel_offer(&second.id);
+    assert_eq!(token_client.balance(&buyer), 1_000);
+
+    let offers = mp_client.get_offers_by_buyer(&buyer, &None, &None);
+    assert_eq!(offers.len(), 2);
+    assert_eq!(
+        mp_client.get_offers_by_buyer(&buyer, &Some(1), &Some(5)),
+        vec![&env, mp_client.get_offer(&second.id)]
+    );
+    assert!(offers
+        .iter()
+        .all(|offer| offer.status == OfferStatus::Cancelled));
+    assert_eq!(
+        mp_client.try_get_offer(&3),
+        Err(Ok(ContractError::OfferNotFound))
+    );
+}
@@ -0,0 +1,150 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+use crate::{
+    error::ContractError,
+    storage::{AcceptedToken, ItemInfo},
+    test::setup::{deploy_token_contract, generate_marketplace_and_collection_client, WEEKLY},
+};
+
+#[test]
+fn seller_should_pick_an_accepted_payment_token() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let default_token = deploy_token_contract(&env, &Address::generate(&env));
+    let other_token = deploy_token_contract(&env, &Address::generate(&env));
+    other_token.mint(&seller, &5);
+    other_token.mint(&bidder, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &default_token.address,
+        None,
+        None,
+    );
+
+    assert_eq!(
+        mp_client.get_accepted_tokens(),
+        vec![
+            &env,
+            AcceptedToken {
+                token: default_token.address.clone(),
+                creation_fee: 10,
+                min_price: 0,
+            }
+        ]
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+
+    assert_eq!(
+        mp_client.try_create_auction(
+            &item_info,
+            &seller,
+            &WEEKLY,
+            &Some(other_token.address.clone()),
+            &None
+        ),
+        Err(Ok(ContractError::TokenNotAccepted))
+    );
+
+    mp_client.add_accepted_token(&other_token.address, &5, &100);
+    assert_eq!(mp_client.get_accepted_tokens().len(), 2);
+
+    assert_eq!(
+        mp_client.try_create_auction(
+            &ItemInfo {
+                buy_now_price: Some(99),
+                ..item_info.clone()
+            },
+            &seller,
+            &WEEKLY,
+            &Some(other_token.address.clone()),
+            &None
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    let auction = mp_client.create_auction(
+        &item_info,
+        &seller,
+        &WEEKLY,
+        &Some(other_token.address.clone()),
+        &None,
+    );
+
+    // the creation fee is paid in the chosen token and its minimum price is the reserve
+    assert_eq!(auction.auction_token, other_token.address);
+    assert_eq!(auction.item_info.minimum_price, Some(100));
+    assert_eq!(other_token.balance(&seller), 0);
+    assert_eq!(mp_client.get_accrued_fees(&other_token.address), 5);
+
+    mp_client.place_bid(&auction.id, &bidder, &150);
+    assert_eq!(other_token.balance(&bidder), 850);
+}
+
+#[test]
+fn admin_should_update_and_remove_accepted_tokens() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let default_token = deploy_token_contract(&env, &Address::generate(&env));
+    default_token.mint(&seller, &50);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &default_token.address,
+        None,
+        None,
+    );
+
+    mp_client.add_accepted_token(&default_token.address, &20, &0);
+    assert_eq!(
+        mp_client.get_accepted_tokens(),
+        vec![
+            &env,
+            AcceptedToken {
+                token: default_token.address.clone(),
+                creation_fee: 20,
+                min_price: 0,
+            }
+        ]
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    assert_eq!(default_token.balance(&seller), 30);
+
+    mp_client.remove_accepted_token(&default_token.address);
+    assert_eq!(mp_client.get_accepted_tokens(), vec![&env]);
+
+    assert_eq!(
+        mp_client.try_create_auction(&item_info, &seller, &WEEKLY, &None, &None),
+        Err(Ok(ContractError::TokenNotAccepted))
+    );
+    assert_eq!(
+        mp_client.try_remove_accepted_token(&default_token.address),
+        Err(Ok(ContractError::TokenNotAccepted))
+    );
+}
@@ -0,0 +1,174 @@
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+
+use crate::{
+    error::ContractError,
+    storage::{BidderRestriction, ItemInfo, TokenGate},
+    test::setup::{
+        create_and_initialize_collection, deploy_token_contract,
+        generate_marketplace_and_collection_client, WEEKLY,
+    },
+};
+
+#[test]
+fn allowlisted_auction_should_accept_only_listed_bidders() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let allowed = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&allowed, &200);
+    token_client.mint(&stranger, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: Some(50),
+        amount: 2,
+    };
+    let restriction = BidderRestriction::Allowlist(vec![&env, allowed.clone()]);
+    mp_client.create_private_auction(&item_info, &seller, &WEEKLY, &None, &None, &restriction);
+
+    assert_eq!(mp_client.get_bidder_restriction(&1), Some(restriction));
+
+    assert_eq!(
+        mp_client.try_place_bid(&1, &stranger, &10),
+        Err(Ok(ContractError::BidderNotEligible))
+    );
+    assert_eq!(
+        mp_client.try_buy_now(&1, &stranger, &1),
+        Err(Ok(ContractError::BidderNotEligible))
+    );
+    assert_eq!(token_client.balance(&stranger), 100);
+
+    mp_client.place_bid(&1, &allowed, &10);
+    mp_client.buy_now(&1, &allowed, &2);
+    assert_eq!(collection_client.balance_of(&allowed, &1), 2);
+}
+
+#[test]
+fn token_gated_auction_should_accept_only_holders() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let small_holder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&holder, &100);
+    token_client.mint(&small_holder, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    let pass_collection = create_and_initialize_collection(&env, &seller, "Pass", "PASS");
+    pass_collection.safe_transfer_from(&seller, &seller, &holder, &1, &2);
+    pass_collection.safe_transfer_from(&seller, &seller, &small_holder, &1, &1);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    let restriction = BidderRestriction::TokenGate(TokenGate {
+        collection_addr: pass_collection.address.clone(),
+        item_id: 1,
+        min_balance: 2,
+    });
+    mp_client.create_private_auction(&item_info, &seller, &WEEKLY, &None, &None, &restriction);
+
+    assert_eq!(
+        mp_client.try_place_bid(&1, &small_holder, &10),
+        Err(Ok(ContractError::BidderNotEligible))
+    );
+    mp_client.place_bid(&1, &holder, &10);
+
+    // eligibility is checked on every bid
+    pass_collection.safe_transfer_from(&holder, &holder, &small_holder, &1, &1);
+    mp_client.place_bid(&1, &small_holder, &20);
+    assert_eq!(
+        mp_client.try_place_bid(&1, &holder, &30),
+        Err(Ok(ContractError::BidderNotEligible))
+    );
+}
+
+#[test]
+fn create_private_auction_should_reject_invalid_restrictions() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+
+    assert_eq!(
+        mp_client.try_create_private_auction(
+            &item_info,
+            &seller,
+            &WEEKLY,
+            &None,
+            &None,
+            &BidderRestriction::Allowlist(vec![&env])
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+    assert_eq!(
+        mp_client.try_create_private_auction(
+            &item_info,
+            &seller,
+            &WEEKLY,
+            &None,
+            &None,
+            &BidderRestriction::TokenGate(TokenGate {
+                collection_addr: collection_client.address.clone(),
+                item_id: 1,
+                min_balance: 0,
+            })
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    // public auctions have no restriction
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    assert_eq!(mp_client.get_bidder_restriction(&1), None);
+}
@@ -0,0 +1,68 @@
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+use crate::{
+    error::ContractError,
+    test::setup::{
+        create_multiple_auctions, deploy_token_contract, generate_marketplace_and_collection_client,
+    },
+};
+
+#[test]
+fn outbid_amounts_should_accrue_until_withdrawn() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder_a = Address::generate(&env);
+    let bidder_b = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &20);
+    token_client.mint(&bidder_a, &100);
+    token_client.mint(&bidder_b, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    create_multiple_auctions(&mp_client, &seller, &collection_client, 2);
+
+    mp_client.place_bid(&1, &bidder_a, &10);
+    mp_client.place_bid(&2, &bidder_a, &20);
+    mp_client.place_bid(&1, &bidder_b, &15);
+    mp_client.place_bid(&2, &bidder_b, &25);
+
+    // the outbid amounts stay with the marketplace until the bidder pulls them
+    assert_eq!(token_client.balance(&bidder_a), 70);
+    assert_eq!(
+        mp_client.pending_refund(&bidder_a, &token_client.address),
+        30
+    );
+    assert_eq!(
+        mp_client.pending_refund(&bidder_b, &token_client.address),
+        0
+    );
+
+    assert_eq!(
+        mp_client.withdraw_refund(&bidder_a, &token_client.address),
+        30
+    );
+    assert_eq!(token_client.balance(&bidder_a), 100);
+    assert_eq!(
+        mp_client.pending_refund(&bidder_a, &token_client.address),
+        0
+    );
+
+    assert_eq!(
+        mp_client.try_withdraw_refund(&bidder_a, &token_client.address),
+        Err(Ok(ContractError::NoRefundPending))
+    );
+    assert_eq!(
+        mp_client.try_withdraw_refund(&bidder_b, &token_client.address),
+        Err(Ok(ContractError::NoRefundPending))
+    );
+}
@@ -0,0 +1,217 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Bytes, Env,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{AuctionStatus, ItemInfo},
+    test::setup::{
+        deploy_token_contract, generate_marketplace_and_collection_client, withdraw_refunds, WEEKLY,
+    },
+};
+
+#[test]
+fn finalize_auction_should_pay_royalty_to_creator() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let creator = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &creator,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    // the creator sold the item to `seller`, who is now reselling it
+    collection_client.mint(&creator, &seller, &1, &1);
+    // 5%
+    collection_client.set_default_royalty(&creator, &creator, &500);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.place_bid(&1, &bidder, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+    mp_client.finalize_auction(&1);
+    withdraw_refunds(&mp_client, &token_client.address, &[&creator, &seller]);
+
+    assert_eq!(token_client.balance(&creator), 50);
+    assert_eq!(token_client.balance(&seller), 950);
+    assert_eq!(token_client.balance(&bidder), 0);
+    // only the auction creation fee stays with the marketplace
+    assert_eq!(token_client.balance(&mp_client.address), 10);
+    assert_eq!(collection_client.balance_of(&bidder, &1), 1);
+    assert_eq!(mp_client.get_auction(&1).status, AuctionStatus::Ended);
+}
+
+#[test]
+fn royalty_should_be_capped_by_the_marketplace() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let creator = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &creator,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    collection_client.mint(&creator, &seller, &1, &1);
+    // the creator asks for 50%, the marketplace honors at most 10%
+    collection_client.set_royalty(&creator, &1, &creator, &5_000);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.place_bid(&1, &bidder, &1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEKLY + 1);
+    mp_client.finalize_auction(&1);
+    withdraw_refunds(&mp_client, &token_client.address, &[&creator, &seller]);
+
+    assert_eq!(token_client.balance(&creator), 100);
+    assert_eq!(token_client.balance(&seller), 900);
+}
+
+#[test]
+fn buy_now_should_pay_royalty_to_creator() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let creator = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&buyer, &500);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &creator,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    collection_client.create_token(&creator, &2, &Bytes::new(&env), &None, &true, &0);
+    collection_client.mint(&creator, &seller, &2, &1);
+    // 2.5% for id #2 only
+    collection_client.set_royalty(&creator, &2, &creator, &250);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 2,
+        minimum_price: None,
+        buy_now_price: Some(400),
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.buy_now(&1, &buyer, &1);
+    withdraw_refunds(&mp_client, &token_client.address, &[&creator, &seller]);
+
+    assert_eq!(token_client.balance(&creator), 10);
+    assert_eq!(token_client.balance(&seller), 390);
+    assert_eq!(token_client.balance(&buyer), 100);
+    assert_eq!(collection_client.balance_of(&buyer, &2), 1);
+}
+
+#[test]
+fn creator_selling_own_item_should_receive_the_full_price() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&creator, &10);
+    token_client.mint(&buyer, &500);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &creator,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    collection_client.set_default_royalty(&creator, &creator, &500);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: Some(500),
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &creator, &WEEKLY, &None, &None);
+    mp_client.buy_now(&1, &buyer, &1);
+    // a single credit carries the full price as no royalty is split off
+    assert_eq!(
+        mp_client.pending_refund(&creator, &token_client.address),
+        500
+    );
+    mp_client.withdraw_refund(&creator, &token_client.address);
+
+    assert_eq!(token_client.balance(&creator), 500);
+    assert_eq!(token_client.balance(&buyer), 0);
+}
+
+#[test]
+fn update_config_should_change_the_royalty_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &admin);
+    let (mp_client, _) =
+        generate_marketplace_and_collection_client(&env, &admin, &token_client.address, None, None);
+
+    assert_eq!(mp_client.get_config().max_royalty_bps, 1_000);
+
+    mp_client.update_config(&Some(2_500), &None, &None, &None, &None, &None);
+    assert_eq!(mp_client.get_config().max_royalty_bps, 2_500);
+
+    assert_eq!(
+        mp_client.try_update_config(&Some(10_001), &None, &None, &None, &None, &None),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+    assert_eq!(mp_client.get_config().max_royalty_bps, 2_500);
+}
@@ -0,0 +1,201 @@
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, Env, Vec,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{Auction, AuctionStatus, ItemInfo},
+    test::setup::{deploy_token_contract, generate_marketplace_and_collection_client, DAY, WEEKLY},
+};
+
+#[test]
+fn scheduled_auction_should_open_for_bids_at_its_start_time() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &100);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: Some(50),
+        amount: 1,
+    };
+    let auction = mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &Some(DAY));
+
+    assert_eq!(auction.status, AuctionStatus::Scheduled);
+    assert_eq!(auction.end_time, DAY + WEEKLY);
+    // the item is escrowed right away
+    assert_eq!(collection_client.balance_of(&mp_client.address, &1), 1);
+
+    assert_eq!(
+        mp_client.try_place_bid(&1, &bidder, &10),
+        Err(Ok(ContractError::AuctionNotStarted))
+    );
+    assert_eq!(
+        mp_client.try_buy_now(&1, &bidder, &1),
+        Err(Ok(ContractError::AuctionNotStarted))
+    );
+    assert_eq!(
+        mp_client.try_pause(&1),
+        Err(Ok(ContractError::AuctionNotActive))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+
+    assert_eq!(mp_client.get_auction(&1).status, AuctionStatus::Active);
+    mp_client.place_bid(&1, &bidder, &10);
+    assert_eq!(mp_client.get_highest_bid(&1).bid, 10);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY + WEEKLY + 1);
+    mp_client.finalize_auction(&1);
+    assert_eq!(collection_client.balance_of(&bidder, &1), 1);
+}
+
+#[test]
+fn create_auction_should_reject_start_time_in_the_past() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    assert_eq!(
+        mp_client.try_create_auction(&item_info, &seller, &WEEKLY, &None, &Some(DAY - 1)),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+
+    // starting now is the same as not scheduling the auction
+    let auction = mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &Some(DAY));
+    assert_eq!(auction.status, AuctionStatus::Active);
+}
+
+#[test]
+fn upcoming_auctions_should_be_sorted_by_start_time() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &40);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+    collection_client.mint(&seller, &seller, &1, &2);
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &Some(3 * DAY));
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &None);
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &Some(DAY));
+    mp_client.create_auction(&item_info, &seller, &WEEKLY, &None, &Some(2 * DAY));
+
+    let upcoming_ids = || {
+        let mut ids = vec![&env];
+        for auction in mp_client
+            .get_upcoming_auctions(&None, &None)
+            .auctions
+            .iter()
+        {
+            ids.push_back(auction.id);
+        }
+        ids
+    };
+
+    assert_eq!(upcoming_ids(), vec![&env, 3, 4, 1]);
+    assert_eq!(
+        mp_client
+            .get_auctions_by_status(&AuctionStatus::Scheduled, &None, &None)
+            .auctions
+            .len(),
+        3
+    );
+
+    // a cancelled auction is no longer upcoming
+    mp_client.cancel_auction(&4);
+    assert_eq!(upcoming_ids(), vec![&env, 3, 1]);
+
+    // started auctions are not upcoming but active, even before anyone interacts with them
+    env.ledger().with_mut(|li| li.timestamp = DAY);
+    assert_eq!(upcoming_ids(), vec![&env, 1]);
+
+    let ids = |auctions: Vec<Auction>| {
+        let mut ids = vec![&env];
+        for auction in auctions.iter() {
+            ids.push_back(auction.id);
+        }
+        ids
+    };
+    assert_eq!(
+        ids(mp_client
+            .get_auctions_by_status(&AuctionStatus::Active, &None, &None)
+            .auctions),
+        vec![&env, 2, 3]
+    );
+    assert_eq!(
+        ids(mp_client
+            .get_auctions_by_status(&AuctionStatus::Scheduled, &None, &None)
+            .auctions),
+        vec![&env, 1]
+    );
+    assert_eq!(
+        ids(mp_client.get_ending_soon_auctions(&None, &None).auctions),
+        vec![&env, 2, 3]
+    );
+    assert_eq!(
+        ids(mp_client.get_active_auctions(&None, &None)),
+        vec![&env, 2, 3]
+    );
+
+    // reading the auction reports it as active without writing anything
+    assert_eq!(mp_client.get_auction(&3).status, AuctionStatus::Active);
+    assert!(env.events().all().is_empty());
+}
@@ -0,0 +1,326 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    xdr::ToXdr,
+    Address, Bytes, BytesN, Env,
+};
+
+use crate::{
+    error::ContractError,
+    storage::{AuctionStatus, ItemInfo, SealedAuctionParams, SealedBidPricing},
+    test::setup::{
+        deploy_token_contract, generate_marketplace_and_collection_client, withdraw_refunds, DAY,
+    },
+};
+
+fn commitment(
+    env: &Env,
+    auction_id: u64,
+    bidder: &Address,
+    bid_amount: u64,
+    salt: &BytesN<32>,
+) -> BytesN<32> {
+    let mut payload = Bytes::from_array(env, &auction_id.to_be_bytes());
+    payload.append(&bidder.clone().to_xdr(env));
+    payload.append(&Bytes::from_array(env, &bid_amount.to_be_bytes()));
+    payload.append(&salt.clone().into());
+
+    env.crypto().sha256(&payload).to_bytes()
+}
+
+#[test]
+fn second_price_auction_should_charge_the_second_highest_bid() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder_a = Address::generate(&env);
+    let bidder_b = Address::generate(&env);
+    let bidder_c = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder_a, &1_000);
+    token_client.mint(&bidder_b, &1_000);
+    token_client.mint(&bidder_c, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_sealed_auction(
+        &item_info,
+        &seller,
+        &None,
+        &SealedAuctionParams {
+            commit_duration: DAY,
+            reveal_duration: DAY,
+            deposit: 50,
+            pricing: SealedBidPricing::SecondPrice,
+        },
+    );
+
+    let salt_a = BytesN::from_array(&env, &[1; 32]);
+    let salt_b = BytesN::from_array(&env, &[2; 32]);
+    let salt_c = BytesN::from_array(&env, &[3; 32]);
+
+    mp_client.commit_bid(&1, &bidder_a, &commitment(&env, 1, &bidder_a, 300, &salt_a));
+    mp_client.commit_bid(&1, &bidder_b, &commitment(&env, 1, &bidder_b, 200, &salt_b));
+    mp_client.commit_bid(&1, &bidder_c, &commitment(&env, 1, &bidder_c, 500, &salt_c));
+    assert_eq!(token_client.balance(&bidder_a), 950);
+
+    assert_eq!(
+        mp_client.try_commit_bid(&1, &bidder_a, &commitment(&env, 1, &bidder_a, 400, &salt_a)),
+        Err(Ok(ContractError::BidAlreadyCommitted))
+    );
+    assert_eq!(
+        mp_client.try_reveal_bid(&1, &bidder_a, &300, &salt_a),
+        Err(Ok(ContractError::InvalidAuctionPhase))
+    );
+    assert_eq!(
+        mp_client.try_place_bid(&1, &bidder_a, &300),
+        Err(Ok(ContractError::InvalidAuctionType))
+    );
+    assert_eq!(
+        mp_client.try_cancel_auction(&1),
+        Err(Ok(ContractError::AuctionCannotBeCancelled))
+    );
+    assert_eq!(
+        mp_client.try_pause(&1),
+        Err(Ok(ContractError::InvalidAuctionType))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = DAY + 1);
+
+    let late_bidder = Address::generate(&env);
+    assert_eq!(
+        mp_client.try_commit_bid(
+            &1,
+            &late_bidder,
+            &commitment(&env, 1, &late_bidder, 900, &salt_a)
+        ),
+        Err(Ok(ContractError::InvalidAuctionPhase))
+    );
+    assert_eq!(
+        mp_client.try_reveal_bid(&1, &bidder_a, &301, &salt_a),
+        Err(Ok(ContractError::InvalidReveal))
+    );
+
+    mp_client.reveal_bid(&1, &bidder_a, &300, &salt_a);
+    mp_client.reveal_bid(&1, &bidder_b, &200, &salt_b);
+    // bidder c never reveals
+    assert_eq!(token_client.balance(&bidder_a), 700);
+    assert_eq!(
+        mp_client.get_sealed_bid(&1, &bidder_a).revealed_bid,
+        Some(300)
+    );
+
+    assert_eq!(
+        mp_client.try_finalize_auction(&1),
+        Err(Ok(ContractError::AuctionNotFinished))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 2 * DAY + 1);
+    mp_client.finalize_auction(&1);
+
+    let auction = mp_client.get_auction(&1);
+    assert_eq!(auction.status, AuctionStatus::Ended);
+    assert_eq!(auction.highest_bid, Some(200));
+    assert_eq!(mp_client.get_highest_bid(&1).bidder, bidder_a);
+
+    assert_eq!(collection_client.balance_of(&bidder_a, &1), 1);
+    withdraw_refunds(
+        &mp_client,
+        &token_client.address,
+        &[&bidder_a, &bidder_b, &bidder_c, &seller],
+    );
+    assert_eq!(token_client.balance(&bidder_a), 800);
+    assert_eq!(token_client.balance(&bidder_b), 1_000);
+    assert_eq!(token_client.balance(&bidder_c), 1_000);
+    assert_eq!(token_client.balance(&seller), 200);
+}
+
+#[test]
+fn first_price_auction_should_refund_everyone_when_minimum_price_not_reached() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder_a = Address::generate(&env);
+    let bidder_b = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder_a, &1_000);
+    token_client.mint(&bidder_b, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: Some(500),
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_sealed_auction(
+        &item_info,
+        &seller,
+        &None,
+        &SealedAuctionParams {
+            commit_duration: DAY,
+            reveal_duration: DAY,
+            deposit: 100,
+            pricing: SealedBidPricing::FirstPrice,
+        },
+    );
+
+    let salt_a = BytesN::from_array(&env, &[1; 32]);
+    let salt_b = BytesN::from_array(&env, &[2; 32]);
+
+    mp_client.commit_bid(&1, &bidder_a, &commitment(&env, 1, &bidder_a, 400, &salt_a));
+    mp_client.commit_bid(&1, &bidder_b, &commitment(&env, 1, &bidder_b, 50, &salt_b));
+
+    env.ledger().with_mut(|li| li.timestamp = DAY + 1);
+    mp_client.reveal_bid(&1, &bidder_a, &400, &salt_a);
+    mp_client.reveal_bid(&1, &bidder_b, &50, &salt_b);
+    assert_eq!(token_client.balance(&bidder_a), 600);
+    assert_eq!(token_client.balance(&bidder_b), 900);
+
+    env.ledger().with_mut(|li| li.timestamp = 2 * DAY + 1);
+    mp_client.finalize_auction(&1);
+
+    let auction = mp_client.get_auction(&1);
+    assert_eq!(auction.status, AuctionStatus::Ended);
+    assert_eq!(auction.highest_bid, None);
+
+    assert_eq!(collection_client.balance_of(&seller, &1), 2);
+    withdraw_refunds(&mp_client, &token_client.address, &[&bidder_a, &bidder_b]);
+    assert_eq!(token_client.balance(&bidder_a), 1_000);
+    assert_eq!(token_client.balance(&bidder_b), 1_000);
+}
+
+#[test]
+fn create_sealed_auction_should_reject_buy_now_price() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: Some(100),
+        amount: 1,
+    };
+
+    assert_eq!(
+        mp_client.try_create_sealed_auction(
+            &item_info,
+            &seller,
+            &None,
+            &SealedAuctionParams {
+                commit_duration: DAY,
+                reveal_duration: DAY,
+                deposit: 10,
+                pricing: SealedBidPricing::FirstPrice
+            }
+        ),
+        Err(Ok(ContractError::InvalidInputs))
+    );
+}
+
+#[test]
+fn copied_commitment_should_not_be_revealed_by_another_bidder() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    let shill = Address::generate(&env);
+
+    let token_client = deploy_token_contract(&env, &Address::generate(&env));
+    token_client.mint(&seller, &10);
+    token_client.mint(&bidder, &1_000);
+    token_client.mint(&shill, &1_000);
+
+    let (mp_client, collection_client) = generate_marketplace_and_collection_client(
+        &env,
+        &seller,
+        &token_client.address,
+        None,
+        None,
+    );
+
+    let item_info = ItemInfo {
+        collection_addr: collection_client.address.clone(),
+        item_id: 1,
+        minimum_price: None,
+        buy_now_price: None,
+        amount: 1,
+    };
+    mp_client.create_sealed_auction(
+        &item_info,
+        &seller,
+        &None,
+        &SealedAuctionParams {
+            commit_duration: DAY,
+            reveal_duration: DAY,
+            deposit: 50,
+            pricing: SealedBidPricing::SecondPrice,
+        },
+    );
+
+    let salt = BytesN::from_array(&env, &[1; 32]);
+    let bidder_commitment = commitment(&env, 1, &bidder, 300, &salt);
+    mp_client.commit_bid(&1, &bidder, &bidder_commitment);
+    mp_client.commit_bid(&1, &shill, &bidder_commitment);
+
+    env.ledger().with_mut(|li| li.timestamp = DAY + 1);
+    mp_client.reveal_bid(&1, &bidder, &300, &salt);
+    assert_eq!(
+        mp_client.try_reveal_bid(&1, &shill, &300, &salt),
+        Err(Ok(ContractError::InvalidReveal))
+    );
+
+    // without a second revealed bid the winner pays only their own bid
+    env.ledger().with_mut(|li| li.timestamp = 2 * DAY + 1);
+    mp_client.finalize_auction(&1);
+
+    assert_eq!(collection_client.balance_of(&bidder, &1), 1);
+    withdraw_refunds(&mp_client, &token_client.address, &[&bidder, &shill]);
+    assert_eq!(token_client.balance(&bidder), 700);
+    assert_eq!(token_client.balance(&shill), 1_000);
+}
@@ -1,11 +1,10 @@
-use soroban_sdk::{
-    testutils::Address as _, token::TokenClient, xdr::ToXdr, Address, Bytes, Env, FromVal, String,
-};
+use soroban_sdk::{testutils::Address as _, xdr::ToXdr, Address, Bytes, Env, FromVal, String};
 
 use crate::{
     collection::{self, Client},
     contract::{MarketplaceContract, MarketplaceContractClient},
     storage::ItemInfo,
+    token,
 };
 
 pub const WEEKLY: u64 = 604_800u64;
@@ -14,7 +13,7 @@ pub const FOUR_HOURS: u64 = 14_400u64;
 const TOKEN_WASM: &[u8] =
     include_bytes!("../../../../target/wasm32-unknown-unknown/release/soroban_token_contract.wasm");
 
-pub fn deploy_token_contract<'a>(env: &Env, admin: &Address) -> TokenClient<'a> {
+pub fn deploy_token_contract<'a>(env: &Env, admin: &Address) -> token::Client<'a> {
     let token_contract = env.register(
         TOKEN_WASM,
         (
@@ -25,7 +24,7 @@ pub fn deploy_token_contract<'a>(env: &Env, admin: &Address) -> TokenClient<'a>
         ),
     );
 
-    TokenClient::new(env, &token_contract)
+    token::Client::new(env, &token_contract)
 }
 
 pub mod auctions_wasm {
@@ -43,7 +42,7 @@ pub fn generate_marketplace_and_collection_client<'a>(
 ) -> (MarketplaceContractClient<'a>, collection::Client<'a>) {
     let mp_client = MarketplaceContractClient::new(env, &env.register(MarketplaceContract, ()));
 
-    mp_client.initialize(admin, auction_token, &10);
+    mp_client.initialize(admin, auction_token, &10, &1_000, &0, admin);
 
     let alt_name = String::from_str(env, "Stellar kitties");
     let alt_symbol = String::from_str(env, "STK");
@@ -54,6 +53,7 @@ pub fn generate_marketplace_and_collection_client<'a>(
 
     let collection_client = collection::Client::new(env, &collection_addr);
     collection_client.initialize(admin, &name, &symbol);
+    collection_client.create_token(admin, &1, &Bytes::new(env), &None, &true, &0);
     collection_client.mint(admin, admin, &1, &2);
 
     (mp_client, collection_client)
@@ -66,6 +66,16 @@ pub fn create_multiple_auctions(
     number_of_auctions_to_make: usize,
 ) {
     for idx in 1..=number_of_auctions_to_make {
+        if collection_client.try_token_info(&(idx as u64)).is_err() {
+            collection_client.create_token(
+                seller,
+                &(idx as u64),
+                &Bytes::new(&collection_client.env),
+                &None,
+                &true,
+                &0,
+            );
+        }
         collection_client.mint(seller, seller, &(idx as u64), &2);
 
         let item_info = ItemInfo {
@@ -75,11 +85,11 @@ pub fn create_multiple_auctions(
             buy_now_price: None,
             amount: 1,
         };
-        mp_client.create_auction(&item_info, seller, &WEEKLY);
+        mp_client.create_auction(&item_info, seller, &WEEKLY, &None, &None);
     }
 }
 
-/// This also mints 5 items of id #1 to the owner of the collection
+/// This also registers id #1 and mints 5 items of it to the owner of the collection
 pub fn create_and_initialize_collection<'a>(
     env: &Env,
     seller: &Address,
@@ -101,7 +111,21 @@ pub fn create_and_initialize_collection<'a>(
     let collection_client = collection::Client::new(env, &collection_addr);
     collection_client.initialize(seller, &collection_name, &collection_symbol);
 
+    collection_client.create_token(seller, &1, &Bytes::new(env), &None, &true, &0);
     collection_client.mint(seller, seller, &1, &5);
 
     collection_client
 }
+
+/// Withdraws the refunds credited to each of the `bidders`, if any
+pub fn withdraw_refunds(
+    mp_client: &MarketplaceContractClient,
+    token: &Address,
+    accounts: &[&Address],
+) {
+    for account in accounts {
+        if mp_client.pending_refund(account, token) > 0 {
+            mp_client.withdraw_refund(account, token);
+        }
+    }
+}
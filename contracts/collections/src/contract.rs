@@ -4,10 +4,14 @@ use crate::{
     error::ContractError,
     storage::{
         utils::{
-            get_admin_old, get_balance_of, is_initialized, save_admin_old, save_config,
-            set_initialized, update_balance_of,
+            are_upgrades_locked, decrease_supply, get_admin_old, get_balance_of, get_max_supply,
+            get_royalty, get_token_ids, get_token_info, get_total_supply, increase_supply,
+            is_initialized, is_token_registered, save_admin_old, save_config, save_max_supply,
+            save_royalty, save_token_info, set_initialized, update_balance_of, update_total_supply,
+            validate_royalty,
         },
-        Config, DataKey, OperatorApprovalKey, TransferApprovalKey, URIValue, ADMIN,
+        Config, DataKey, OperatorApprovalKey, RoyaltyInfo, TokenInfo, TransferApprovalKey,
+        URIValue, ADMIN, MAX_BASIS_POINTS,
     },
     ttl::{BUMP_AMOUNT, LIFETIME_THRESHOLD},
 };
@@ -88,17 +92,17 @@ impl Collections {
         Ok(batch_balances)
     }
 
-    // Grants or revokes permission to `operator` to manage the caller's assets
+    // Grants or revokes permission to `operator` to manage all of `owner`'s assets
     #[allow(dead_code)]
     pub fn set_approval_for_all(
         env: Env,
+        owner: Address,
         operator: Address,
         approved: bool,
     ) -> Result<(), ContractError> {
-        let admin = get_admin_old(&env)?;
-        admin.require_auth();
+        owner.require_auth();
 
-        if admin == operator {
+        if owner == operator {
             log!(
                 &env,
                 "Collection: Set approval for all: Cannot set approval for self. Operator: ",
@@ -108,7 +112,7 @@ impl Collections {
         }
 
         let data_key = DataKey::OperatorApproval(OperatorApprovalKey {
-            owner: admin.clone(),
+            owner: owner.clone(),
             operator: operator.clone(),
         });
 
@@ -118,7 +122,7 @@ impl Collections {
             .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
 
         env.events()
-            .publish(("Set approval for", "Sender: "), admin);
+            .publish(("Set approval for", "Sender: "), owner);
         env.events().publish(
             ("Set approval for", "Set approval for operator: "),
             operator,
@@ -129,27 +133,28 @@ impl Collections {
         Ok(())
     }
 
+    // Grants or revokes permission to `operator` to transfer `owner`'s tokens of type `nft_id`
     #[allow(dead_code)]
     pub fn set_approval_for_transfer(
         env: Env,
+        owner: Address,
         operator: Address,
         nft_id: u64,
         approved: bool,
     ) -> Result<(), ContractError> {
-        let admin = get_admin_old(&env)?;
-        admin.require_auth();
+        owner.require_auth();
 
-        if admin == operator {
+        if owner == operator {
             log!(
                 &env,
-                "Collection: Set approval for transfer: Trying to authorize admin. Operator: ",
+                "Collection: Set approval for transfer: Cannot set approval for self. Operator: ",
                 operator
             );
             return Err(ContractError::CannotApproveSelf);
         }
 
         let data_key = DataKey::TransferApproval(TransferApprovalKey {
-            owner: admin.clone(),
+            owner: owner.clone(),
             operator: operator.clone(),
             nft_id,
         });
@@ -160,7 +165,7 @@ impl Collections {
             .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
 
         env.events()
-            .publish(("Set approval for transfer", "Sender: "), admin);
+            .publish(("Set approval for transfer", "Sender: "), owner);
         env.events().publish(
             (
                 "Set approval for transfer",
@@ -226,8 +231,9 @@ impl Collections {
         id: u64,
         transfer_amount: u64,
     ) -> Result<(), ContractError> {
-        // if the sender is NOT transferring his own tokens and he's not authorized for transfer then we fail
-        if sender != from && !Self::is_authorized_for_transfer(&env, &sender, id) {
+        // if the sender is NOT transferring his own tokens and `from` has not authorized him
+        // for transfer then we fail
+        if !Self::is_authorized_for_transfer(&env, &from, &sender, id) {
             log!(
                 &env,
                 "Collection: Safe Transfer From: Unauthorized.",
@@ -240,6 +246,8 @@ impl Collections {
 
         sender.require_auth();
 
+        Self::ensure_transferable(&env, id)?;
+
         let from_balance = get_balance_of(&env, &from, id)?;
         let rcpt_balance = get_balance_of(&env, &to, id)?;
 
@@ -292,8 +300,8 @@ impl Collections {
             return Err(ContractError::IdsAmountsLengthMismatch);
         }
 
-        for id in 0..ids.len() {
-            if sender != from && !Self::is_authorized_for_transfer(&env, &sender, id.into()) {
+        for id in ids.iter() {
+            if !Self::is_authorized_for_transfer(&env, &from, &sender, id) {
                 log!(
                     &env,
                     "Collection: Safe Transfer From: Unauthorized.",
@@ -311,6 +319,8 @@ impl Collections {
             let id = ids.get(idx).ok_or(ContractError::InvalidIdIndex)?;
             let amount = amounts.get(idx).ok_or(ContractError::InvalidAmountIndex)?;
 
+            Self::ensure_transferable(&env, id)?;
+
             let sender_balance = get_balance_of(&env, &from, id)?;
             let rcpt_balance = get_balance_of(&env, &to, id)?;
 
@@ -355,13 +365,16 @@ impl Collections {
         id: u64,
         amount: u64,
     ) -> Result<(), ContractError> {
-        if !Self::is_authorized_for_all(&env, &sender) {
+        if !Self::is_admin(&env, &sender) {
             log!(&env, "Collections: Mint: Unauthorized. Sender: ", sender);
             return Err(ContractError::Unauthorized);
         }
 
         sender.require_auth();
 
+        get_token_info(&env, id)?;
+        increase_supply(&env, id, amount)?;
+
         let current_balance = get_balance_of(&env, &to, id)?;
         update_balance_of(&env, &to, id, current_balance + amount)?;
 
@@ -382,7 +395,7 @@ impl Collections {
         ids: Vec<u64>,
         amounts: Vec<u64>,
     ) -> Result<(), ContractError> {
-        if !Self::is_authorized_for_all(&env, &sender) {
+        if !Self::is_admin(&env, &sender) {
             log!(&env, "Collections: Mint: Unauthorized. Sender: ", sender);
             return Err(ContractError::Unauthorized);
         }
@@ -404,6 +417,9 @@ impl Collections {
             let id = ids.get(idx).ok_or(ContractError::InvalidIdIndex)?;
             let amount = amounts.get(idx).ok_or(ContractError::InvalidAmountIndex)?;
 
+            get_token_info(&env, id)?;
+            increase_supply(&env, id, amount)?;
+
             let current_balance = get_balance_of(&env, &to, id)?;
             update_balance_of(&env, &to, id, current_balance + amount)?;
         }
@@ -425,8 +441,8 @@ impl Collections {
         id: u64,
         amount: u64,
     ) -> Result<(), ContractError> {
-        if sender != from && !Self::is_authorized_for_all(&env, &sender) {
-            log!(&env, "Collections: Mint: Unauthorized. Sender: ", sender);
+        if sender != from && !Self::is_approved_for_all(env.clone(), from.clone(), sender.clone()) {
+            log!(&env, "Collections: Burn: Unauthorized. Sender: ", sender);
             return Err(ContractError::Unauthorized);
         }
 
@@ -447,6 +463,7 @@ impl Collections {
         }
 
         update_balance_of(&env, &from, id, current_balance - amount)?;
+        decrease_supply(&env, id, amount);
 
         env.events().publish(("burn", "from: "), from);
         env.events().publish(("burn", "id: "), id);
@@ -464,8 +481,12 @@ impl Collections {
         ids: Vec<u64>,
         amounts: Vec<u64>,
     ) -> Result<(), ContractError> {
-        if sender != from && !Self::is_authorized_for_all(&env, &sender) {
-            log!(&env, "Collections: Mint: Unauthorized. Sender: ", sender);
+        if sender != from && !Self::is_approved_for_all(env.clone(), from.clone(), sender.clone()) {
+            log!(
+                &env,
+                "Collections: Burn batch: Unauthorized. Sender: ",
+                sender
+            );
             return Err(ContractError::Unauthorized);
         }
 
@@ -501,6 +522,7 @@ impl Collections {
                 return Err(ContractError::InsufficientBalance);
             }
             update_balance_of(&env, &from, id, current_balance - amount)?;
+            decrease_supply(&env, id, amount);
         }
 
         env.events().publish(("burn batch", "from: "), from);
@@ -510,21 +532,257 @@ impl Collections {
         Ok(())
     }
 
+    // Registers a new token type `id`. Only registered token types can be minted
+    #[allow(dead_code)]
+    pub fn create_token(
+        env: Env,
+        sender: Address,
+        id: u64,
+        uri: Bytes,
+        max_supply: Option<u64>,
+        transferable: bool,
+        legacy_supply: u64,
+    ) -> Result<TokenInfo, ContractError> {
+        if !Self::is_admin(&env, &sender) {
+            log!(
+                &env,
+                "Collections: Create token: Unauthorized. Sender: ",
+                sender
+            );
+            return Err(ContractError::Unauthorized);
+        }
+        sender.require_auth();
+
+        if is_token_registered(&env, id) {
+            log!(
+                &env,
+                "Collections: Create token: Token already registered: ",
+                id
+            );
+            return Err(ContractError::TokenAlreadyExists);
+        }
+
+        // tokens of that id minted before supply tracking was introduced are not counted, so the
+        // admin declares them when registering the id
+        update_total_supply(&env, id, legacy_supply);
+
+        if let Some(max_supply) = max_supply {
+            if max_supply == 0 || max_supply < legacy_supply {
+                log!(
+                    &env,
+                    "Collections: Create token: Invalid max supply: ",
+                    max_supply,
+                    " legacy supply: ",
+                    legacy_supply
+                );
+                return Err(ContractError::InvalidMaxSupply);
+            }
+            save_max_supply(&env, id, max_supply);
+        }
+
+        let token_info = TokenInfo { id, transferable };
+        save_token_info(&env, &token_info);
+        Self::save_uri(&env, id, &uri);
+
+        env.events().publish(("create token", "id: "), id);
+        env.events().publish(("create token", "uri: "), uri);
+        env.events()
+            .publish(("create token", "max supply: "), max_supply);
+        env.events()
+            .publish(("create token", "transferable: "), transferable);
+        env.events()
+            .publish(("create token", "legacy supply: "), legacy_supply);
+
+        Ok(token_info)
+    }
+
+    // Returns the registration info for token type `id`
+    #[allow(dead_code)]
+    pub fn token_info(env: Env, id: u64) -> Result<TokenInfo, ContractError> {
+        get_token_info(&env, id)
+    }
+
+    // Returns the registered token type ids, in order of registration
+    #[allow(dead_code)]
+    pub fn get_token_ids(env: Env, start_index: Option<u32>, limit: Option<u32>) -> Vec<u64> {
+        get_token_ids(&env, start_index, limit)
+    }
+
+    // Sets the maximum supply for a token type `id`. Once set the cap cannot be changed
+    #[allow(dead_code)]
+    pub fn set_max_supply(
+        env: Env,
+        sender: Address,
+        id: u64,
+        max_supply: u64,
+    ) -> Result<(), ContractError> {
+        if !Self::is_admin(&env, &sender) {
+            log!(
+                &env,
+                "Collections: Set max supply: Unauthorized. Sender: ",
+                sender
+            );
+            return Err(ContractError::Unauthorized);
+        }
+        sender.require_auth();
+
+        get_token_info(&env, id)?;
+
+        if get_max_supply(&env, id).is_some() {
+            log!(
+                &env,
+                "Collections: Set max supply: Max supply already set for id: ",
+                id
+            );
+            return Err(ContractError::MaxSupplyAlreadySet);
+        }
+
+        let total_supply = get_total_supply(&env, id);
+        if max_supply == 0 || max_supply < total_supply {
+            log!(
+                &env,
+                "Collections: Set max supply: Invalid max supply: ",
+                max_supply,
+                " current total supply: ",
+                total_supply
+            );
+            return Err(ContractError::InvalidMaxSupply);
+        }
+
+        save_max_supply(&env, id, max_supply);
+
+        env.events().publish(("set max supply", "id: "), id);
+        env.events()
+            .publish(("set max supply", "max supply: "), max_supply);
+
+        Ok(())
+    }
+
+    // Returns the amount of tokens of token type `id` currently in circulation
+    #[allow(dead_code)]
+    pub fn total_supply(env: Env, id: u64) -> u64 {
+        get_total_supply(&env, id)
+    }
+
+    // Returns the maximum supply of token type `id`, if one has been set
+    #[allow(dead_code)]
+    pub fn max_supply(env: Env, id: u64) -> Option<u64> {
+        get_max_supply(&env, id)
+    }
+
+    // Returns true if there is at least one token of token type `id` in circulation
+    #[allow(dead_code)]
+    pub fn exists(env: Env, id: u64) -> bool {
+        get_total_supply(&env, id) > 0
+    }
+
+    // Sets the royalty applied to every token type that has no royalty of its own
+    #[allow(dead_code)]
+    pub fn set_default_royalty(
+        env: Env,
+        sender: Address,
+        receiver: Address,
+        basis_points: u32,
+    ) -> Result<(), ContractError> {
+        if !Self::is_admin(&env, &sender) {
+            log!(
+                &env,
+                "Collections: Set default royalty: Unauthorized. Sender: ",
+                sender
+            );
+            return Err(ContractError::Unauthorized);
+        }
+        sender.require_auth();
+
+        let royalty = RoyaltyInfo {
+            receiver: receiver.clone(),
+            basis_points,
+        };
+        validate_royalty(&env, &royalty)?;
+        save_royalty(&env, &DataKey::DefaultRoyalty, &royalty);
+
+        env.events()
+            .publish(("set default royalty", "receiver: "), receiver);
+        env.events()
+            .publish(("set default royalty", "basis points: "), basis_points);
+
+        Ok(())
+    }
+
+    // Sets a royalty for token type `id` that overrides the default royalty
+    #[allow(dead_code)]
+    pub fn set_royalty(
+        env: Env,
+        sender: Address,
+        id: u64,
+        receiver: Address,
+        basis_points: u32,
+    ) -> Result<(), ContractError> {
+        if !Self::is_admin(&env, &sender) {
+            log!(
+                &env,
+                "Collections: Set royalty: Unauthorized. Sender: ",
+                sender
+            );
+            return Err(ContractError::Unauthorized);
+        }
+        sender.require_auth();
+
+        get_token_info(&env, id)?;
+
+        let royalty = RoyaltyInfo {
+            receiver: receiver.clone(),
+            basis_points,
+        };
+        validate_royalty(&env, &royalty)?;
+        save_royalty(&env, &DataKey::Royalty(id), &royalty);
+
+        env.events().publish(("set royalty", "id: "), id);
+        env.events()
+            .publish(("set royalty", "receiver: "), receiver);
+        env.events()
+            .publish(("set royalty", "basis points: "), basis_points);
+
+        Ok(())
+    }
+
+    // Returns the royalty receiver and the royalty amount owed for selling token type `id` at
+    // `sale_price`
+    #[allow(dead_code)]
+    pub fn royalty_info(
+        env: Env,
+        id: u64,
+        sale_price: u64,
+    ) -> Result<(Address, u64), ContractError> {
+        let royalty = get_royalty(&env, &DataKey::Royalty(id))
+            .or_else(|| get_royalty(&env, &DataKey::DefaultRoyalty))
+            .ok_or_else(|| {
+                log!(
+                    &env,
+                    "Collections: Royalty info: No royalty set for id: ",
+                    id
+                );
+                ContractError::NoRoyaltySet
+            })?;
+
+        let royalty_amount =
+            (sale_price as u128 * royalty.basis_points as u128 / MAX_BASIS_POINTS as u128) as u64;
+
+        Ok((royalty.receiver, royalty_amount))
+    }
+
     // Sets a new URI for a token type `id`
     #[allow(dead_code)]
     pub fn set_uri(env: Env, sender: Address, id: u64, uri: Bytes) -> Result<(), ContractError> {
-        if !Self::is_authorized_for_all(&env, &sender) {
+        if !Self::is_admin(&env, &sender) {
             log!(&env, "Collections: Mint: Unauthorized. Sender: ", sender);
             return Err(ContractError::Unauthorized);
         }
         sender.require_auth();
 
-        env.storage()
-            .persistent()
-            .set(&DataKey::Uri(id), &URIValue { uri: uri.clone() });
-        env.storage()
-            .persistent()
-            .extend_ttl(&DataKey::Uri(id), LIFETIME_THRESHOLD, BUMP_AMOUNT);
+        get_token_info(&env, id)?;
+
+        Self::save_uri(&env, id, &uri);
 
         env.events().publish(("set uri", "sender: "), sender);
         env.events().publish(("set uri", "id: "), id);
@@ -536,7 +794,7 @@ impl Collections {
     // Sets the main image(logo) for the collection
     #[allow(dead_code)]
     pub fn set_collection_uri(env: Env, sender: Address, uri: Bytes) -> Result<(), ContractError> {
-        if !Self::is_authorized_for_all(&env, &sender) {
+        if !Self::is_admin(&env, &sender) {
             log!(&env, "Collections: Mint: Unauthorized. Sender: ", sender);
             return Err(ContractError::Unauthorized);
         }
@@ -593,11 +851,34 @@ impl Collections {
         let admin: Address = get_admin_old(&env)?;
         admin.require_auth();
 
+        if are_upgrades_locked(&env) {
+            log!(&env, "Collections: Upgrade: Upgrades are locked");
+            return Err(ContractError::UpgradesLocked);
+        }
+
         env.deployer().update_current_contract_wasm(new_wasm_hash);
 
         Ok(())
     }
 
+    // once locked the collection keeps its current implementation for good
+    #[allow(dead_code)]
+    pub fn lock_upgrades(env: Env) -> Result<(), ContractError> {
+        let admin: Address = get_admin_old(&env)?;
+        admin.require_auth();
+
+        crate::storage::utils::lock_upgrades(&env);
+
+        env.events().publish(("lock upgrades", "admin: "), admin);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn are_upgrades_locked(env: Env) -> bool {
+        are_upgrades_locked(&env)
+    }
+
     #[allow(dead_code)]
     pub fn migrate_admin(env: Env) -> Result<(), ContractError> {
         let admin: Address = get_admin_old(&env)?;
@@ -618,17 +899,44 @@ impl Collections {
         Ok(mabye_config)
     }
 
-    fn is_authorized_for_transfer(env: &Env, sender: &Address, nft_id: u64) -> bool {
-        let admin = get_admin_old(env).expect("no admin found");
+    fn is_authorized_for_transfer(
+        env: &Env,
+        owner: &Address,
+        sender: &Address,
+        nft_id: u64,
+    ) -> bool {
+        owner == sender
+            || Self::is_approved_for_all(env.clone(), owner.clone(), sender.clone())
+            || Self::is_approved_for_transfer(env.clone(), owner.clone(), sender.clone(), nft_id)
+    }
 
-        admin == sender.clone()
-            || Self::is_approved_for_all(env.clone(), admin.clone(), sender.clone())
-            || Self::is_approved_for_transfer(env.clone(), admin.clone(), sender.clone(), nft_id)
+    fn ensure_transferable(env: &Env, nft_id: u64) -> Result<(), ContractError> {
+        if !get_token_info(env, nft_id)?.transferable {
+            log!(
+                env,
+                "Collections: Transfer: Token is not transferable: ",
+                nft_id
+            );
+            return Err(ContractError::TokenNotTransferable);
+        }
+
+        Ok(())
+    }
+
+    fn save_uri(env: &Env, id: u64, uri: &Bytes) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Uri(id), &URIValue { uri: uri.clone() });
+        env.storage()
+            .persistent()
+            .extend_ttl(&DataKey::Uri(id), LIFETIME_THRESHOLD, BUMP_AMOUNT);
     }
 
-    fn is_authorized_for_all(env: &Env, sender: &Address) -> bool {
+    // Operator approvals only cover the tokens of the approving holder, administering the
+    // collection is reserved to the admin
+    fn is_admin(env: &Env, sender: &Address) -> bool {
         let admin = get_admin_old(env).expect("no admin found");
 
-        admin == sender.clone() || Self::is_approved_for_all(env.clone(), admin, sender.clone())
+        admin == *sender
     }
 }
@@ -17,4 +17,12 @@ pub enum ContractError {
     AlreadyInitialized = 10,
     InvalidAmountIndex = 11,
     InvalidId = 12,
+    MaxSupplyAlreadySet = 13,
+    MaxSupplyExceeded = 14,
+    InvalidMaxSupply = 15,
+    TokenAlreadyExists = 16,
+    TokenNotTransferable = 17,
+    InvalidRoyalty = 18,
+    NoRoyaltySet = 19,
+    UpgradesLocked = 20,
 }
@@ -6,6 +6,12 @@ type Balance = u64;
 
 pub const ADMIN: Symbol = symbol_short!("ADMIN");
 
+// default page size when querying the registered token ids
+pub const DEFAULT_LIMIT: u32 = 10;
+
+// royalties are expressed in basis points, 10_000 being 100% of the sale price
+pub const MAX_BASIS_POINTS: u32 = 10_000;
+
 // Struct to represent the operator approval status
 #[derive(Clone)]
 #[contracttype]
@@ -40,6 +46,13 @@ pub enum DataKey {
     CollectionUri,
     Config,
     IsInitialized,
+    TotalSupply(NftId),
+    MaxSupply(NftId),
+    TokenInfo(NftId),
+    TokenIds,
+    DefaultRoyalty,
+    Royalty(NftId),
+    UpgradesLocked,
 }
 
 // Struct to represent token URI
@@ -49,6 +62,28 @@ pub struct URIValue {
     pub uri: Bytes,
 }
 
+/// Struct that describes a registered token type
+///
+/// * `id` - The id of the token type
+/// * `transferable` - Whether holders are allowed to transfer tokens of that type
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct TokenInfo {
+    pub id: u64,
+    pub transferable: bool,
+}
+
+/// Struct that describes who receives royalties from secondary sales and how much
+///
+/// * `receiver` - The `Address` that receives the royalty payments
+/// * `basis_points` - The share of the sale price paid as royalty, where 10_000 is 100%
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct RoyaltyInfo {
+    pub receiver: Address,
+    pub basis_points: u32,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct Config {
@@ -58,11 +93,16 @@ pub struct Config {
 
 pub mod utils {
 
-    use soroban_sdk::{Address, Env, Map};
+    use soroban_sdk::{log, vec, Address, Env, Map, Vec};
 
     use crate::error::ContractError;
 
-    use super::{Balance, Config, DataKey, TokenId, ADMIN};
+    use super::{
+        Balance, Config, DataKey, RoyaltyInfo, TokenId, TokenInfo, ADMIN, DEFAULT_LIMIT,
+        MAX_BASIS_POINTS,
+    };
+
+    use crate::ttl::{BUMP_AMOUNT, LIFETIME_THRESHOLD};
 
     pub fn get_balance_of(env: &Env, owner: &Address, id: u64) -> Result<u64, ContractError> {
         let balance_map: Map<TokenId, Balance> = env
@@ -99,6 +139,176 @@ pub mod utils {
         Ok(())
     }
 
+    pub fn get_total_supply(env: &Env, id: TokenId) -> Balance {
+        let data_key = DataKey::TotalSupply(id);
+        let total_supply = env.storage().persistent().get(&data_key).unwrap_or(0u64);
+
+        env.storage().persistent().has(&data_key).then(|| {
+            env.storage()
+                .persistent()
+                .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT)
+        });
+
+        total_supply
+    }
+
+    pub fn update_total_supply(env: &Env, id: TokenId, new_supply: Balance) {
+        let data_key = DataKey::TotalSupply(id);
+        env.storage().persistent().set(&data_key, &new_supply);
+        env.storage()
+            .persistent()
+            .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+    }
+
+    pub fn get_max_supply(env: &Env, id: TokenId) -> Option<Balance> {
+        let data_key = DataKey::MaxSupply(id);
+        let max_supply = env.storage().persistent().get(&data_key);
+
+        env.storage().persistent().has(&data_key).then(|| {
+            env.storage()
+                .persistent()
+                .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT)
+        });
+
+        max_supply
+    }
+
+    pub fn save_max_supply(env: &Env, id: TokenId, max_supply: Balance) {
+        let data_key = DataKey::MaxSupply(id);
+        env.storage().persistent().set(&data_key, &max_supply);
+        env.storage()
+            .persistent()
+            .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+    }
+
+    /// Increases the total supply of `id` by `amount`, failing when that would go over the
+    /// maximum supply for that id.
+    pub fn increase_supply(env: &Env, id: TokenId, amount: Balance) -> Result<(), ContractError> {
+        let new_supply = get_total_supply(env, id) + amount;
+
+        if let Some(max_supply) = get_max_supply(env, id) {
+            if new_supply > max_supply {
+                log!(
+                    env,
+                    "Collections: Increase supply: Max supply exceeded for id: ",
+                    id,
+                    " max supply: ",
+                    max_supply
+                );
+                return Err(ContractError::MaxSupplyExceeded);
+            }
+        }
+
+        update_total_supply(env, id, new_supply);
+
+        Ok(())
+    }
+
+    pub fn decrease_supply(env: &Env, id: TokenId, amount: Balance) {
+        // the legacy supply declared when registering an id may fall short of the real one
+        let current_supply = get_total_supply(env, id);
+        update_total_supply(env, id, current_supply.saturating_sub(amount));
+    }
+
+    pub fn get_token_info(env: &Env, id: TokenId) -> Result<TokenInfo, ContractError> {
+        let data_key = DataKey::TokenInfo(id);
+        let token_info = env.storage().persistent().get(&data_key).ok_or_else(|| {
+            log!(
+                env,
+                "Collections: Get token info: Token not registered: ",
+                id
+            );
+            ContractError::InvalidId
+        })?;
+
+        env.storage()
+            .persistent()
+            .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+
+        Ok(token_info)
+    }
+
+    pub fn save_token_info(env: &Env, token_info: &TokenInfo) {
+        let data_key = DataKey::TokenInfo(token_info.id);
+        env.storage().persistent().set(&data_key, token_info);
+        env.storage()
+            .persistent()
+            .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+
+        let mut token_ids: Vec<TokenId> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TokenIds)
+            .unwrap_or(vec![env]);
+        token_ids.push_back(token_info.id);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::TokenIds, &token_ids);
+        env.storage()
+            .persistent()
+            .extend_ttl(&DataKey::TokenIds, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+    }
+
+    pub fn is_token_registered(env: &Env, id: TokenId) -> bool {
+        env.storage().persistent().has(&DataKey::TokenInfo(id))
+    }
+
+    pub fn get_token_ids(env: &Env, start_index: Option<u32>, limit: Option<u32>) -> Vec<TokenId> {
+        let token_ids: Vec<TokenId> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TokenIds)
+            .unwrap_or(vec![env]);
+
+        env.storage().persistent().has(&DataKey::TokenIds).then(|| {
+            env.storage().persistent().extend_ttl(
+                &DataKey::TokenIds,
+                LIFETIME_THRESHOLD,
+                BUMP_AMOUNT,
+            )
+        });
+
+        let start_index = start_index.unwrap_or(0).min(token_ids.len());
+        let end_index = start_index
+            .saturating_add(limit.unwrap_or(DEFAULT_LIMIT))
+            .min(token_ids.len());
+
+        token_ids.slice(start_index..end_index)
+    }
+
+    pub fn validate_royalty(env: &Env, royalty: &RoyaltyInfo) -> Result<(), ContractError> {
+        if royalty.basis_points > MAX_BASIS_POINTS {
+            log!(
+                env,
+                "Collections: Validate royalty: Basis points over the maximum: ",
+                royalty.basis_points
+            );
+            return Err(ContractError::InvalidRoyalty);
+        }
+
+        Ok(())
+    }
+
+    pub fn save_royalty(env: &Env, data_key: &DataKey, royalty: &RoyaltyInfo) {
+        env.storage().persistent().set(data_key, royalty);
+        env.storage()
+            .persistent()
+            .extend_ttl(data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+    }
+
+    pub fn get_royalty(env: &Env, data_key: &DataKey) -> Option<RoyaltyInfo> {
+        let royalty = env.storage().persistent().get(data_key);
+
+        env.storage().persistent().has(data_key).then(|| {
+            env.storage()
+                .persistent()
+                .extend_ttl(data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT)
+        });
+
+        royalty
+    }
+
     pub fn save_config(env: &Env, config: Config) -> Result<(), ContractError> {
         env.storage().persistent().set(&DataKey::Config, &config);
 
@@ -158,4 +368,17 @@ pub mod utils {
             .persistent()
             .set(&DataKey::IsInitialized, &true);
     }
+
+    pub fn are_upgrades_locked(env: &Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UpgradesLocked)
+            .unwrap_or(false)
+    }
+
+    pub fn lock_upgrades(env: &Env) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::UpgradesLocked, &true);
+    }
 }
@@ -1,4 +1,4 @@
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{testutils::Address as _, Address, Bytes, Env, String};
 
 use crate::contract::{Collections, CollectionsClient};
 
@@ -22,3 +22,10 @@ pub fn initialize_collection_contract<'a>(
 
     collections
 }
+
+/// Registers each of the `ids` as a transferable token type without a max supply
+pub fn create_tokens(env: &Env, client: &CollectionsClient, admin: &Address, ids: &[u64]) {
+    for id in ids {
+        client.create_token(admin, id, &Bytes::new(env), &None, &true, &0);
+    }
+}
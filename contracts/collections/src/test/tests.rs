@@ -1,12 +1,12 @@
-use soroban_sdk::{testutils::Address as _, vec, Address, Bytes, Env, String};
+use soroban_sdk::{testutils::Address as _, vec, Address, Bytes, BytesN, Env, String};
 
 use crate::{
     contract::{Collections, CollectionsClient},
     error::ContractError,
-    storage::{Config, URIValue},
+    storage::{Config, TokenInfo, URIValue},
 };
 
-use super::setup::initialize_collection_contract;
+use super::setup::{create_tokens, initialize_collection_contract};
 use test_case::test_case;
 
 #[test]
@@ -65,6 +65,8 @@ fn mint_and_check_balance() {
 
     let collections_client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &collections_client, &admin, &[1, 2, 3, 4, 5]);
+
     collections_client.mint(&admin, &user, &1, &10);
 
     collections_client.mint(&admin, &user, &2, &10);
@@ -90,6 +92,8 @@ fn mint_batch_and_balance_of_batch() {
 
     let collections_client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &collections_client, &admin, &[1, 2, 3, 4, 5]);
+
     collections_client.mint_batch(&admin, &user_a, &id_list, &amounts_list);
     collections_client.mint_batch(&admin, &user_b, &id_list, &amounts_list);
     collections_client.mint_batch(&admin, &user_c, &id_list, &amounts_list);
@@ -115,7 +119,7 @@ fn approval_tests() {
 
     let collectoins_client = initialize_collection_contract(&env, Some(&user), None, None);
 
-    collectoins_client.set_approval_for_all(&operator, &true);
+    collectoins_client.set_approval_for_all(&user, &operator, &true);
 
     assert!(collectoins_client.is_approved_for_all(&user, &operator));
 }
@@ -131,12 +135,14 @@ fn safe_transfer_from() {
 
     let client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
     client.mint(&admin, &user_a, &1, &1);
 
     assert_eq!(client.balance_of(&user_a, &1), 1u64);
     assert_eq!(client.balance_of(&user_b, &1), 0u64);
 
-    client.safe_transfer_from(&admin, &user_a, &user_b, &1, &1);
+    client.safe_transfer_from(&user_a, &user_a, &user_b, &1, &1);
 
     assert_eq!(client.balance_of(&user_a, &1), 0u64);
     assert_eq!(client.balance_of(&user_b, &1), 1u64);
@@ -156,6 +162,8 @@ fn safe_batch_transfer() {
 
     let client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
     let ids = vec![&env, 1, 2, 3, 4, 5];
     let amounts = vec![&env, 5, 5, 5, 5, 5];
     client.mint_batch(&admin, &user_a, &ids, &amounts);
@@ -166,7 +174,7 @@ fn safe_batch_transfer() {
         vec![&env, 5, 0, 0, 0, 0]
     );
 
-    client.safe_batch_transfer_from(&admin, &user_a, &user_b, &ids, &amounts);
+    client.safe_batch_transfer_from(&user_a, &user_a, &user_b, &ids, &amounts);
     assert_eq!(
         client.balance_of_batch(&accounts, &ids),
         vec![&env, 0, 5, 0, 0, 0]
@@ -183,10 +191,12 @@ fn burning() {
 
     let collectoins_client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &collectoins_client, &admin, &[1, 2, 3, 4, 5]);
+
     collectoins_client.mint(&admin, &user, &1, &2);
     assert_eq!(collectoins_client.balance_of(&user, &1), 2);
 
-    collectoins_client.burn(&admin, &user, &1, &1);
+    collectoins_client.burn(&user, &user, &1, &1);
     assert_eq!(collectoins_client.balance_of(&user, &1), 1);
 }
 
@@ -200,6 +210,8 @@ fn batch_burning() {
 
     let collections_client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &collections_client, &admin, &[1, 2, 3, 4, 5]);
+
     collections_client.mint_batch(
         &admin,
         &user,
@@ -223,7 +235,7 @@ fn batch_burning() {
     );
 
     collections_client.burn_batch(
-        &admin,
+        &user,
         &user,
         &vec![&env, 1, 2, 3, 4, 5],
         &vec![&env, 5, 10, 15, 20, 25],
@@ -255,6 +267,8 @@ fn test_uri() {
 
     let collections_client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &collections_client, &admin, &[1, 2, 3, 4, 5]);
+
     collections_client.mint(&admin, &user, &1, &5);
 
     let secret_uri = Bytes::from_slice(
@@ -316,7 +330,7 @@ fn should_fail_when_set_approval_for_all_tries_to_approve_self() {
     let collections_client = initialize_collection_contract(&env, Some(&admin), None, None);
 
     assert_eq!(
-        collections_client.try_set_approval_for_all(&admin, &true),
+        collections_client.try_set_approval_for_all(&admin, &admin, &true),
         Err(Ok(ContractError::CannotApproveSelf))
     )
 }
@@ -332,6 +346,8 @@ fn should_fail_when_sender_balance_not_enough() {
 
     let client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
     // mint 1
     client.mint(&admin, &user_a, &1, &1);
     client.mint(&admin, &user_b, &1, &1);
@@ -340,7 +356,7 @@ fn should_fail_when_sender_balance_not_enough() {
 
     // try to send 10
     assert_eq!(
-        client.try_safe_transfer_from(&admin, &user_a, &user_b, &1, &10),
+        client.try_safe_transfer_from(&user_a, &user_a, &user_b, &1, &10),
         Err(Ok(ContractError::InsufficientBalance))
     )
 }
@@ -355,6 +371,8 @@ fn safe_batch_transfer_should_fail_when_id_mismatch() {
 
     let client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
     let ids = vec![&env, 1, 2, 3, 4, 5];
     let amounts = vec![&env, 5, 5, 5, 5, 5];
     client.mint_batch(&admin, &user_a, &ids, &amounts);
@@ -390,13 +408,15 @@ fn safe_batch_transfer_should_fail_when_insufficient_balance(
 
     let client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
     let ids = vec![&env, 1, 2, 3, 4, 5];
     let amounts = vec![&env, 5, 5, 5, 5, 5];
     client.mint_batch(&admin, &user_a, &ids, &amounts);
 
     assert_eq!(
         client.try_safe_batch_transfer_from(
-            &admin,
+            &user_a,
             &user_a,
             &Address::generate(&env),
             &ids,
@@ -538,8 +558,10 @@ fn should_transfer_when_sender_is_operator() {
 
     let client = initialize_collection_contract(&env, Some(&user_a), None, None);
 
+    create_tokens(&env, &client, &user_a, &[1, 2, 3, 4, 5]);
+
     client.mint(&user_a, &user_a, &1, &1);
-    client.set_approval_for_all(&operator, &true);
+    client.set_approval_for_all(&user_a, &operator, &true);
 
     assert_eq!(client.balance_of(&user_a, &1), 1u64);
     assert_eq!(client.balance_of(&user_b, &1), 0u64);
@@ -560,7 +582,7 @@ fn should_fail_when_admin_tries_to_set_himself_as_operator_for_approval_for_tran
     let collectoins_client = initialize_collection_contract(&env, Some(&admin), None, None);
 
     assert_eq!(
-        collectoins_client.try_set_approval_for_transfer(&admin, &1, &true),
+        collectoins_client.try_set_approval_for_transfer(&admin, &admin, &1, &true),
         Err(Ok(ContractError::CannotApproveSelf))
     );
 }
@@ -600,10 +622,12 @@ fn safe_transfer_from_should_fail_when_user_is_not_authorized() {
 
     let collections_client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &collections_client, &admin, &[1, 2, 3, 4, 5]);
+
     // admin mints himself a new NFT
     collections_client.mint(&admin, &admin, &1, &2);
     // admin sets operator to be able to do as they like with the NFT
-    collections_client.set_approval_for_transfer(&operator, &1, &true);
+    collections_client.set_approval_for_transfer(&admin, &operator, &1, &true);
 
     // rogue user tries to steal, but fails
     assert_eq!(
@@ -622,7 +646,7 @@ fn safe_transfer_from_should_fail_when_user_is_not_authorized() {
     assert_eq!(collections_client.balance_of(&rcpt, &1), 1);
 
     // admin revokes rights
-    collections_client.set_approval_for_transfer(&operator, &1, &false);
+    collections_client.set_approval_for_transfer(&admin, &operator, &1, &false);
 
     assert_eq!(
         collections_client.try_safe_transfer_from(&operator, &admin, &rcpt, &1, &1),
@@ -642,13 +666,20 @@ fn grant_all_permissions_to_user_then_withdraw_them() {
 
     let collections_client = initialize_collection_contract(&env, Some(&user_a), None, None);
 
-    collections_client.set_approval_for_all(&operator, &true);
+    create_tokens(&env, &collections_client, &user_a, &[1, 2, 3, 4, 5]);
+
+    // the operator of the admin's own tokens is not granted admin rights
+    collections_client.set_approval_for_all(&user_a, &operator, &true);
+    assert_eq!(
+        collections_client.try_mint(&operator, &rcpt, &1, &2),
+        Err(Ok(ContractError::Unauthorized))
+    );
 
-    collections_client.mint(&operator, &rcpt, &1, &2);
-    collections_client.mint(&operator, &other_rcpt, &1, &1);
+    collections_client.mint(&user_a, &rcpt, &1, &2);
+    collections_client.mint(&user_a, &other_rcpt, &1, &1);
 
     collections_client.mint_batch(
-        &operator,
+        &user_a,
         &rcpt,
         &vec![&env, 1u64, 2u64, 3u64, 4u64, 5u64],
         &vec![&env, 1u64, 1u64, 1u64, 1u64, 1u64],
@@ -672,6 +703,8 @@ fn grant_all_permissions_to_user_then_withdraw_them() {
     );
     assert_eq!(collections_client.balance_of(&other_rcpt, &1), 1);
 
+    // burning is authorized by the holder, not by the admin
+    collections_client.set_approval_for_all(&rcpt, &operator, &true);
     collections_client.burn(&operator, &rcpt, &1, &1);
 
     assert_eq!(collections_client.balance_of(&rcpt, &1), 2);
@@ -701,18 +734,19 @@ fn grant_all_permissions_to_user_then_withdraw_them() {
     );
 
     let uri = Bytes::from_slice(&env, &[44, 55, 66]);
-    collections_client.set_uri(&operator, &1, &uri);
+    collections_client.set_uri(&user_a, &1, &uri);
     assert_eq!(collections_client.uri(&1), URIValue { uri });
 
     let better_uri = Bytes::from_slice(&env, &[42, 7, 13]);
-    collections_client.set_collection_uri(&operator, &better_uri);
+    collections_client.set_collection_uri(&user_a, &better_uri);
     assert_eq!(
         collections_client.collection_uri(),
         URIValue { uri: better_uri }
     );
 
     // now we withdraw our permissions from the operator and we check again
-    collections_client.set_approval_for_all(&operator, &false);
+    collections_client.set_approval_for_all(&user_a, &operator, &false);
+    collections_client.set_approval_for_all(&rcpt, &operator, &false);
 
     assert_eq!(
         collections_client.try_mint(&operator, &rcpt, &10, &1),
@@ -766,6 +800,8 @@ fn safe_batch_transfer_should_succeed_when_sender_from_the_same() {
 
     let client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
     let ids = vec![&env, 1, 2, 3, 4, 5];
     let amounts = vec![&env, 5, 4, 3, 2, 1];
     client.mint_batch(&admin, &user_a, &ids, &amounts);
@@ -827,6 +863,8 @@ fn safe_batch_transfer_should_fail_when_sender_is_not_authorized_to_transfer_fro
 
     let client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
     let ids = vec![&env, 1, 2, 3, 4, 5];
     let amounts = vec![&env, 5, 4, 3, 2, 1];
     client.mint_batch(&admin, &user_a, &ids, &amounts);
@@ -867,8 +905,10 @@ fn safe_transfer_should_work_when_sender_is_from_and_is_authorized() {
 
     let client = initialize_collection_contract(&env, Some(&admin), None, None);
 
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
     client.mint(&admin, &user_a, &1, &1);
-    client.set_approval_for_transfer(&user_a, &1, &true);
+    client.set_approval_for_transfer(&admin, &user_a, &1, &true);
 
     assert_eq!(client.balance_of(&user_a, &1), 1u64);
     assert_eq!(client.balance_of(&user_b, &1), 0u64);
@@ -878,3 +918,521 @@ fn safe_transfer_should_work_when_sender_is_from_and_is_authorized() {
     assert_eq!(client.balance_of(&user_a, &1), 0u64);
     assert_eq!(client.balance_of(&user_b, &1), 1u64);
 }
+
+#[test]
+fn holder_can_approve_operator_for_own_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let marketplace = Address::generate(&env);
+    let rcpt = Address::generate(&env);
+
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
+    client.mint(&admin, &holder, &1, &2);
+    client.mint(&admin, &holder, &2, &2);
+
+    // the approval is stored for the holder and not for the admin
+    client.set_approval_for_transfer(&holder, &marketplace, &1, &true);
+    assert!(client.is_approved_for_transfer(&holder, &marketplace, &1));
+    assert!(!client.is_approved_for_transfer(&admin, &marketplace, &1));
+
+    client.safe_transfer_from(&marketplace, &holder, &rcpt, &1, &1);
+    assert_eq!(client.balance_of(&rcpt, &1), 1);
+
+    // approval for id #1 does not cover id #2
+    assert_eq!(
+        client.try_safe_transfer_from(&marketplace, &holder, &rcpt, &2, &1),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    client.set_approval_for_all(&holder, &marketplace, &true);
+    assert!(client.is_approved_for_all(&holder, &marketplace));
+
+    client.safe_batch_transfer_from(
+        &marketplace,
+        &holder,
+        &rcpt,
+        &vec![&env, 1, 2],
+        &vec![&env, 1, 2],
+    );
+    assert_eq!(
+        client.balance_of_batch(&vec![&env, rcpt.clone(), rcpt.clone()], &vec![&env, 1, 2]),
+        vec![&env, 2, 2]
+    );
+}
+
+#[test]
+fn admin_approvals_do_not_cover_holder_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let operator = Address::generate(&env);
+
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
+    client.mint(&admin, &holder, &1, &2);
+    client.set_approval_for_all(&admin, &operator, &true);
+
+    // neither the admin nor the admin's operator can move or burn the holder's tokens
+    assert_eq!(
+        client.try_safe_transfer_from(&admin, &holder, &operator, &1, &1),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_safe_transfer_from(&operator, &holder, &operator, &1, &1),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_safe_batch_transfer_from(
+            &operator,
+            &holder,
+            &operator,
+            &vec![&env, 1],
+            &vec![&env, 1]
+        ),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_burn(&operator, &holder, &1, &1),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_burn_batch(&admin, &holder, &vec![&env, 1], &vec![&env, 1]),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    assert_eq!(client.balance_of(&holder, &1), 2);
+}
+
+#[test]
+fn total_supply_tracks_mints_and_burns() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user_a = Address::generate(&env);
+    let user_b = Address::generate(&env);
+
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
+    assert_eq!(client.total_supply(&1), 0);
+    assert!(!client.exists(&1));
+
+    client.mint(&admin, &user_a, &1, &10);
+    client.mint_batch(&admin, &user_b, &vec![&env, 1, 2], &vec![&env, 5, 3]);
+
+    assert_eq!(client.total_supply(&1), 15);
+    assert_eq!(client.total_supply(&2), 3);
+    assert!(client.exists(&1));
+    assert!(client.exists(&2));
+
+    // transfers do not change the supply
+    client.safe_transfer_from(&user_a, &user_a, &user_b, &1, &4);
+    assert_eq!(client.total_supply(&1), 15);
+
+    client.burn(&user_a, &user_a, &1, &6);
+    client.burn_batch(&user_b, &user_b, &vec![&env, 1, 2], &vec![&env, 9, 3]);
+
+    assert_eq!(client.total_supply(&1), 0);
+    assert_eq!(client.total_supply(&2), 0);
+    assert!(!client.exists(&1));
+    assert!(!client.exists(&2));
+}
+
+#[test]
+fn mint_should_respect_max_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
+    assert_eq!(client.max_supply(&1), None);
+
+    client.set_max_supply(&admin, &1, &50);
+    assert_eq!(client.max_supply(&1), Some(50));
+
+    client.mint(&admin, &user, &1, &12);
+    assert_eq!(client.total_supply(&1), 12);
+
+    assert_eq!(
+        client.try_mint(&admin, &user, &1, &39),
+        Err(Ok(ContractError::MaxSupplyExceeded))
+    );
+    // duplicated ids in a batch count towards the same cap
+    assert_eq!(
+        client.try_mint_batch(&admin, &user, &vec![&env, 1, 1], &vec![&env, 20, 20]),
+        Err(Ok(ContractError::MaxSupplyExceeded))
+    );
+
+    client.mint_batch(&admin, &user, &vec![&env, 1, 2], &vec![&env, 38, 100]);
+    assert_eq!(client.total_supply(&1), 50);
+    assert_eq!(client.balance_of(&user, &1), 50);
+
+    // burning frees up room under the cap again
+    client.burn(&user, &user, &1, &1);
+    client.mint(&admin, &user, &1, &1);
+    assert_eq!(client.total_supply(&1), 50);
+}
+
+#[test]
+fn set_max_supply_should_fail_when_cap_would_be_changed_or_is_invalid() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    create_tokens(&env, &client, &admin, &[1, 2, 3, 4, 5]);
+
+    assert_eq!(
+        client.try_set_max_supply(&user, &1, &10),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    client.mint(&admin, &user, &1, &5);
+
+    assert_eq!(
+        client.try_set_max_supply(&admin, &1, &4),
+        Err(Ok(ContractError::InvalidMaxSupply))
+    );
+    assert_eq!(
+        client.try_set_max_supply(&admin, &2, &0),
+        Err(Ok(ContractError::InvalidMaxSupply))
+    );
+
+    client.set_max_supply(&admin, &1, &10);
+
+    // the cap can be neither raised nor lowered once set
+    assert_eq!(
+        client.try_set_max_supply(&admin, &1, &100),
+        Err(Ok(ContractError::MaxSupplyAlreadySet))
+    );
+    assert_eq!(
+        client.try_set_max_supply(&admin, &1, &5),
+        Err(Ok(ContractError::MaxSupplyAlreadySet))
+    );
+    assert_eq!(client.max_supply(&1), Some(10));
+}
+
+#[test]
+fn legacy_supply_should_count_towards_max_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    // 12 tokens of id 1 were minted before supply tracking was introduced
+    assert_eq!(
+        client.try_create_token(&admin, &1, &Bytes::new(&env), &Some(11), &true, &12),
+        Err(Ok(ContractError::InvalidMaxSupply))
+    );
+    client.create_token(&admin, &1, &Bytes::new(&env), &Some(50), &true, &12);
+    assert_eq!(client.total_supply(&1), 12);
+
+    assert_eq!(
+        client.try_mint(&admin, &user, &1, &39),
+        Err(Ok(ContractError::MaxSupplyExceeded))
+    );
+    client.mint(&admin, &user, &1, &38);
+    assert_eq!(client.total_supply(&1), 50);
+
+    client.create_token(&admin, &2, &Bytes::new(&env), &None, &true, &12);
+    assert_eq!(
+        client.try_set_max_supply(&admin, &2, &11),
+        Err(Ok(ContractError::InvalidMaxSupply))
+    );
+}
+
+#[test]
+fn create_token_and_query_registered_ids() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    assert_eq!(client.get_token_ids(&None, &None), vec![&env]);
+
+    let uri = Bytes::from_slice(&env, &[42]);
+    let token_info = client.create_token(&admin, &7, &uri, &Some(50), &true, &0);
+    assert_eq!(
+        token_info,
+        TokenInfo {
+            id: 7,
+            transferable: true
+        }
+    );
+    assert_eq!(client.token_info(&7), token_info);
+    assert_eq!(client.uri(&7), URIValue { uri });
+    assert_eq!(client.max_supply(&7), Some(50));
+
+    for id in 8..20u64 {
+        client.create_token(&admin, &id, &Bytes::new(&env), &None, &false, &0);
+    }
+
+    // default page size is 10
+    assert_eq!(
+        client.get_token_ids(&None, &None),
+        vec![&env, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
+    );
+    assert_eq!(
+        client.get_token_ids(&Some(10), &Some(5)),
+        vec![&env, 17, 18, 19]
+    );
+    assert_eq!(client.get_token_ids(&Some(20), &Some(5)), vec![&env]);
+
+    assert_eq!(
+        client.try_create_token(&admin, &7, &Bytes::new(&env), &None, &true, &0),
+        Err(Ok(ContractError::TokenAlreadyExists))
+    );
+    assert_eq!(
+        client.try_create_token(
+            &Address::generate(&env),
+            &100,
+            &Bytes::new(&env),
+            &None,
+            &true,
+            &0
+        ),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_create_token(&admin, &100, &Bytes::new(&env), &Some(0), &true, &0),
+        Err(Ok(ContractError::InvalidMaxSupply))
+    );
+    assert_eq!(
+        client.try_token_info(&100),
+        Err(Ok(ContractError::InvalidId))
+    );
+}
+
+#[test]
+fn should_fail_with_invalid_id_for_unregistered_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    create_tokens(&env, &client, &admin, &[1]);
+    client.mint(&admin, &user, &1, &1);
+
+    assert_eq!(
+        client.try_mint(&admin, &user, &2, &1),
+        Err(Ok(ContractError::InvalidId))
+    );
+    assert_eq!(
+        client.try_mint_batch(&admin, &user, &vec![&env, 1, 2], &vec![&env, 1, 1]),
+        Err(Ok(ContractError::InvalidId))
+    );
+    assert_eq!(
+        client.try_set_uri(&admin, &2, &Bytes::from_slice(&env, &[42])),
+        Err(Ok(ContractError::InvalidId))
+    );
+    assert_eq!(
+        client.try_set_max_supply(&admin, &2, &10),
+        Err(Ok(ContractError::InvalidId))
+    );
+    assert_eq!(
+        client.try_safe_transfer_from(&user, &user, &admin, &2, &0),
+        Err(Ok(ContractError::InvalidId))
+    );
+    assert_eq!(
+        client.try_safe_batch_transfer_from(
+            &user,
+            &user,
+            &admin,
+            &vec![&env, 1, 2],
+            &vec![&env, 1, 0]
+        ),
+        Err(Ok(ContractError::InvalidId))
+    );
+
+    assert_eq!(client.balance_of(&user, &1), 1);
+}
+
+#[test]
+fn non_transferable_tokens_cannot_be_transferred() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let rcpt = Address::generate(&env);
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    client.create_token(&admin, &1, &Bytes::new(&env), &None, &false, &0);
+    client.mint(&admin, &user, &1, &2);
+
+    assert_eq!(
+        client.try_safe_transfer_from(&user, &user, &rcpt, &1, &1),
+        Err(Ok(ContractError::TokenNotTransferable))
+    );
+    assert_eq!(
+        client.try_safe_batch_transfer_from(&user, &user, &rcpt, &vec![&env, 1], &vec![&env, 1]),
+        Err(Ok(ContractError::TokenNotTransferable))
+    );
+
+    // holders can still burn them
+    client.burn(&user, &user, &1, &1);
+    assert_eq!(client.balance_of(&user, &1), 1);
+}
+
+#[test]
+fn royalty_info_uses_default_royalty_and_per_id_overrides() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let collaborator = Address::generate(&env);
+
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+    create_tokens(&env, &client, &admin, &[1, 2]);
+
+    assert_eq!(
+        client.try_royalty_info(&1, &1_000),
+        Err(Ok(ContractError::NoRoyaltySet))
+    );
+
+    // 5%
+    client.set_default_royalty(&admin, &creator, &500);
+    assert_eq!(client.royalty_info(&1, &1_000), (creator.clone(), 50));
+    assert_eq!(client.royalty_info(&2, &1_000), (creator.clone(), 50));
+
+    // 12.5% for id #2 only
+    client.set_royalty(&admin, &2, &collaborator, &1_250);
+    assert_eq!(client.royalty_info(&1, &1_000), (creator.clone(), 50));
+    assert_eq!(client.royalty_info(&2, &1_000), (collaborator.clone(), 125));
+
+    // amounts are rounded down
+    assert_eq!(client.royalty_info(&1, &19), (creator.clone(), 0));
+    assert_eq!(client.royalty_info(&1, &u64::MAX), (creator, u64::MAX / 20));
+}
+
+#[test]
+fn set_royalty_should_fail_with_invalid_input() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+    create_tokens(&env, &client, &admin, &[1]);
+
+    assert_eq!(
+        client.try_set_default_royalty(&creator, &creator, &500),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_royalty(&creator, &1, &creator, &500),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_default_royalty(&admin, &creator, &10_001),
+        Err(Ok(ContractError::InvalidRoyalty))
+    );
+    assert_eq!(
+        client.try_set_royalty(&admin, &1, &creator, &10_001),
+        Err(Ok(ContractError::InvalidRoyalty))
+    );
+    assert_eq!(
+        client.try_set_royalty(&admin, &2, &creator, &500),
+        Err(Ok(ContractError::InvalidId))
+    );
+
+    // 100% is still a valid royalty
+    client.set_royalty(&admin, &1, &creator, &10_000);
+    assert_eq!(client.royalty_info(&1, &1_000), (creator, 1_000));
+}
+
+#[test]
+fn operator_of_admin_tokens_cannot_administer_the_collection() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let marketplace = Address::generate(&env);
+
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    create_tokens(&env, &client, &admin, &[1]);
+    client.mint(&admin, &admin, &1, &2);
+
+    // approving a marketplace to sell the admin's items does not make it a minter
+    client.set_approval_for_all(&admin, &marketplace, &true);
+    client.safe_transfer_from(&marketplace, &admin, &marketplace, &1, &1);
+
+    assert_eq!(
+        client.try_mint(&marketplace, &marketplace, &1, &1),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_mint_batch(&marketplace, &marketplace, &vec![&env, 1], &vec![&env, 1]),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_create_token(&marketplace, &2, &Bytes::new(&env), &None, &true, &0),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_max_supply(&marketplace, &1, &10),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_royalty(&marketplace, &1, &marketplace, &500),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_uri(&marketplace, &1, &Bytes::new(&env)),
+        Err(Ok(ContractError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_collection_uri(&marketplace, &Bytes::new(&env)),
+        Err(Ok(ContractError::Unauthorized))
+    );
+
+    assert_eq!(client.total_supply(&1), 2);
+}
+
+#[test]
+fn locked_collection_cannot_be_upgraded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client = initialize_collection_contract(&env, Some(&admin), None, None);
+
+    assert!(!client.are_upgrades_locked());
+
+    client.lock_upgrades();
+
+    assert!(client.are_upgrades_locked());
+    assert_eq!(
+        client.try_upgrade(&BytesN::from_array(&env, &[0; 32])),
+        Err(Ok(ContractError::UpgradesLocked))
+    );
+}
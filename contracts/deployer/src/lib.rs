@@ -44,6 +44,7 @@ impl CollectionsDeployer {
         admin: Address,
         name: String,
         symbol: String,
+        lock_upgrades: bool,
     ) -> Address {
         admin.require_auth();
         let collections_wasm_hash = get_wasm_hash(&env);
@@ -62,8 +63,15 @@ impl CollectionsDeployer {
         ];
         let _: Val = env.invoke_contract(&deployed_collection, &init_fn, init_fn_args);
 
+        // keeps the collection on the code it was deployed with, which marketplaces may require
+        if lock_upgrades {
+            let lock_fn = Symbol::new(&env, "lock_upgrades");
+            let _: Val = env.invoke_contract(&deployed_collection, &lock_fn, vec![&env]);
+        }
+
         save_collection_with_generic_key(&env, name.clone());
         save_collection_with_admin_address_as_key(&env, admin, deployed_collection.clone(), name);
+        save_deployed_collection(&env, &deployed_collection);
 
         deployed_collection
     }
@@ -108,6 +116,36 @@ impl CollectionsDeployer {
 
         maybe_collections
     }
+
+    /// Tells whether `collection` was deployed by this contract. Unless its upgrades are locked its
+    /// admin may have upgraded it since, so callers should check that too.
+    pub fn is_deployed_collection(env: &Env, collection: Address) -> bool {
+        let data_key = DataKey::DeployedCollection(collection);
+        let is_deployed = env.storage().persistent().has(&data_key);
+
+        is_deployed.then(|| {
+            env.storage()
+                .persistent()
+                .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT)
+        });
+
+        is_deployed
+    }
+
+    /// Records the collections `creator` deployed before deployments were recorded, returning how
+    /// many of them were not recorded yet.
+    #[allow(dead_code)]
+    pub fn register_deployed_collections(env: Env, creator: Address) -> u32 {
+        let mut registered = 0;
+        for entry in Self::query_collection_by_creator(&env, creator).iter() {
+            if !Self::is_deployed_collection(&env, entry.collection.clone()) {
+                save_deployed_collection(&env, &entry.collection);
+                registered += 1;
+            }
+        }
+
+        registered
+    }
 }
 
 // ---------- Storage types ----------
@@ -126,6 +164,7 @@ pub enum DataKey {
     CollectionsWasmHash,
     AllCollections,
     Creator(Address),
+    DeployedCollection(Address),
 }
 
 pub fn set_initialized(env: &Env) {
@@ -225,5 +264,14 @@ pub fn save_collection_with_admin_address_as_key(
         .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
 }
 
+pub fn save_deployed_collection(env: &Env, collection: &Address) {
+    let data_key = DataKey::DeployedCollection(collection.clone());
+
+    env.storage().persistent().set(&data_key, &());
+    env.storage()
+        .persistent()
+        .extend_ttl(&data_key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+}
+
 #[cfg(test)]
 mod tests;
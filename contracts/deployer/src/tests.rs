@@ -1,4 +1,4 @@
-use crate::{CollectionByCreatorResponse, CollectionsDeployer, CollectionsDeployerClient};
+use crate::{CollectionByCreatorResponse, CollectionsDeployer, CollectionsDeployerClient, DataKey};
 #[cfg(test)]
 use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Env, String};
 
@@ -32,9 +32,13 @@ fn test_deploy_collection_from_contract() {
     let name = String::from_str(&env, "Stellar Kitties");
     let symbol = String::from_str(&env, "STK");
 
-    let collection = client.deploy_new_collection(&salt, &creator, &name, &symbol);
+    let collection = client.deploy_new_collection(&salt, &creator, &name, &symbol, &true);
 
     assert_eq!(client.query_all_collections(), vec![&env, name.clone()]);
+    assert!(client.is_deployed_collection(&collection));
+    assert!(!client.is_deployed_collection(&Address::generate(&env)));
+    assert!(collections::Client::new(&env, &collection).are_upgrades_locked());
+
     assert_eq!(
         client.query_collection_by_creator(&creator),
         vec![&env, CollectionByCreatorResponse { collection, name }]
@@ -74,18 +78,21 @@ fn test_deploy_multiple_collections() {
         &creator,
         &first_collection_name,
         &first_collection_symbol,
+        &false,
     );
     let second = client.deploy_new_collection(
         &second_salt,
         &creator,
         &second_collection_name,
         &second_collection_symbol,
+        &false,
     );
     let third = client.deploy_new_collection(
         &third_salt,
         &bob,
         &third_collection_name,
         &third_collection_symbol,
+        &false,
     );
 
     assert_eq!(
@@ -113,6 +120,9 @@ fn test_deploy_multiple_collections() {
         ]
     );
 
+    // upgrades are only locked on request
+    assert!(!collections::Client::new(&env, &third).are_upgrades_locked());
+
     assert_eq!(
         client.query_collection_by_creator(&bob),
         vec![
@@ -138,3 +148,34 @@ fn initialize_twice() {
     deployer_client.initialize(&wasm_hash);
     deployer_client.initialize(&wasm_hash);
 }
+
+#[test]
+fn register_collections_deployed_before_they_were_recorded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client = CollectionsDeployerClient::new(&env, &env.register(CollectionsDeployer, ()));
+    let wasm_hash = env.deployer().upload_contract_wasm(collections::WASM);
+    client.initialize(&wasm_hash);
+
+    let creator = Address::generate(&env);
+    let collection = client.deploy_new_collection(
+        &BytesN::from_array(&env, &[0; 32]),
+        &creator,
+        &String::from_str(&env, "Stellar Kitties"),
+        &String::from_str(&env, "STK"),
+        &false,
+    );
+
+    // collections deployed by earlier versions only show up in the creator's list
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::DeployedCollection(collection.clone()))
+    });
+    assert!(!client.is_deployed_collection(&collection));
+
+    assert_eq!(client.register_deployed_collections(&creator), 1);
+    assert!(client.is_deployed_collection(&collection));
+    assert_eq!(client.register_deployed_collections(&creator), 0);
+}